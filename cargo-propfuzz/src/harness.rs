@@ -0,0 +1,289 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Building test binaries and talking to the propfuzz driver within them.
+
+use anyhow::{anyhow, bail, Context, Result};
+use propfuzz::driver;
+use std::env;
+use std::ffi::OsString;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use structopt::StructOpt;
+
+/// Options passed through to `cargo test` when building test binaries.
+#[derive(Debug, StructOpt)]
+pub struct BuildOpts {
+    /// Path to Cargo.toml.
+    #[structopt(long, parse(from_os_str))]
+    manifest_path: Option<PathBuf>,
+    /// Package to build test binaries for (can be specified multiple times).
+    #[structopt(short, long = "package", number_of_values = 1)]
+    packages: Vec<String>,
+    /// Build test binaries for all packages in the workspace.
+    #[structopt(long)]
+    workspace: bool,
+    /// Only look for targets in library unit tests.
+    #[structopt(long)]
+    lib: bool,
+    /// Only look for targets in the given integration test (can be specified multiple times).
+    #[structopt(long = "test", number_of_values = 1)]
+    tests: Vec<String>,
+    /// Space or comma separated list of features to activate.
+    #[structopt(long)]
+    features: Option<String>,
+    /// Activate all available features.
+    #[structopt(long)]
+    all_features: bool,
+    /// Do not activate the `default` feature.
+    #[structopt(long)]
+    no_default_features: bool,
+    /// Build test binaries in release mode.
+    #[structopt(long)]
+    release: bool,
+    /// Build test binaries with SanitizerCoverage instrumentation, for coverage-guided fuzzing.
+    #[structopt(skip)]
+    pub coverage: bool,
+}
+
+impl BuildOpts {
+    fn cargo_args(&self) -> Vec<OsString> {
+        let mut args: Vec<OsString> = vec![];
+        if let Some(manifest_path) = &self.manifest_path {
+            args.push("--manifest-path".into());
+            args.push(manifest_path.into());
+        }
+        for package in &self.packages {
+            args.push("--package".into());
+            args.push(package.into());
+        }
+        if self.workspace {
+            args.push("--workspace".into());
+        }
+        if self.lib {
+            args.push("--lib".into());
+        }
+        for test in &self.tests {
+            args.push("--test".into());
+            args.push(test.into());
+        }
+        if let Some(features) = &self.features {
+            args.push("--features".into());
+            args.push(features.into());
+        }
+        if self.all_features {
+            args.push("--all-features".into());
+        }
+        if self.no_default_features {
+            args.push("--no-default-features".into());
+        }
+        if self.release {
+            args.push("--release".into());
+        }
+        args
+    }
+}
+
+/// A test binary produced by `cargo test --no-run`.
+#[derive(Clone, Debug)]
+pub struct TestBinary {
+    path: PathBuf,
+    package_dir: PathBuf,
+}
+
+/// A propfuzz target within a test binary.
+#[derive(Clone, Debug)]
+pub struct Target {
+    pub name: String,
+    pub fuzz_default: bool,
+    pub description: String,
+    pub binary: PathBuf,
+    pub package_dir: PathBuf,
+}
+
+impl Target {
+    /// Runs a driver command against this target, returning the exit code of the test binary.
+    pub fn run(&self, command: &str, envs: Vec<(&str, OsString)>) -> Result<i32> {
+        let status = driver_command(&self.binary, &self.package_dir, command)
+            .env(driver::TARGET_ENV, &self.name)
+            .envs(envs)
+            .status()
+            .with_context(|| format!("failed to run {}", self.binary.display()))?;
+        Ok(status.code().unwrap_or(1))
+    }
+}
+
+/// The flags that instrument code for coverage feedback, as read by `propfuzz::coverage`.
+const COVERAGE_RUSTFLAGS: &str = "-Cpasses=sancov-module \
+    -Cllvm-args=-sanitizer-coverage-level=3 \
+    -Cllvm-args=-sanitizer-coverage-inline-8bit-counters";
+
+/// Builds test binaries through `cargo test --no-run`, and returns their paths.
+pub fn build(opts: &BuildOpts) -> Result<Vec<TestBinary>> {
+    let cargo = env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let mut command = Command::new(&cargo);
+    command
+        .arg("test")
+        .arg("--no-run")
+        .arg("--message-format=json-render-diagnostics")
+        .args(opts.cargo_args());
+    if opts.coverage {
+        // Passing in the host target explicitly keeps build scripts and proc macros from being
+        // instrumented, since RUSTFLAGS then only applies to the target.
+        let mut rustflags = env::var("RUSTFLAGS").unwrap_or_default();
+        if !rustflags.is_empty() {
+            rustflags.push(' ');
+        }
+        rustflags.push_str(COVERAGE_RUSTFLAGS);
+        command
+            .arg("--target")
+            .arg(host_target()?)
+            .env("RUSTFLAGS", rustflags);
+    }
+    let mut child = command
+        .stdout(Stdio::piped())
+        .spawn()
+        .context("failed to run cargo test")?;
+
+    let stdout = child.stdout.take().expect("stdout was piped");
+    let mut binaries = vec![];
+    for line in BufReader::new(stdout).lines() {
+        let line = line.context("failed to read output of cargo test")?;
+        let message: serde_json::Value = match serde_json::from_str(&line) {
+            Ok(message) => message,
+            // Build scripts may print non-JSON lines.
+            Err(_) => continue,
+        };
+        if message["reason"] != "compiler-artifact" || message["profile"]["test"] != true {
+            continue;
+        }
+        if let (Some(executable), Some(manifest_path)) = (
+            message["executable"].as_str(),
+            message["manifest_path"].as_str(),
+        ) {
+            let package_dir = Path::new(manifest_path)
+                .parent()
+                .expect("manifest path has a parent")
+                .to_path_buf();
+            binaries.push(TestBinary {
+                path: executable.into(),
+                package_dir,
+            });
+        }
+    }
+
+    let status = child.wait().context("failed to wait for cargo test")?;
+    if !status.success() {
+        bail!("failed to build test binaries");
+    }
+    Ok(binaries)
+}
+
+fn host_target() -> Result<String> {
+    let rustc = env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    let output = Command::new(&rustc)
+        .arg("-vV")
+        .output()
+        .context("failed to run rustc")?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout
+        .lines()
+        .find_map(|line| line.strip_prefix("host: "))
+        .map(|host| host.trim().to_string())
+        .ok_or_else(|| anyhow!("failed to determine host target from `rustc -vV`"))
+}
+
+/// Lists the propfuzz targets in the given test binaries, sorted by name.
+pub fn list_targets(binaries: &[TestBinary]) -> Result<Vec<Target>> {
+    let mut targets = vec![];
+    for binary in binaries {
+        if !has_driver(binary)? {
+            continue;
+        }
+        let output = driver_command(&binary.path, &binary.package_dir, "list")
+            .stderr(Stdio::inherit())
+            .output()
+            .with_context(|| format!("failed to run {}", binary.path.display()))?;
+        if !output.status.success() {
+            bail!("failed to list targets in {}", binary.path.display());
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        for line in stdout.lines() {
+            let mut fields = line.splitn(4, '\t');
+            if fields.next() != Some(driver::LIST_PREFIX) {
+                continue;
+            }
+            let name = fields.next().unwrap_or_default();
+            let fuzz_default = fields.next() == Some("true");
+            let description = fields.next().unwrap_or_default();
+            targets.push(Target {
+                name: name.to_string(),
+                fuzz_default,
+                description: description.to_string(),
+                binary: binary.path.clone(),
+                package_dir: binary.package_dir.clone(),
+            });
+        }
+    }
+
+    targets.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(targets)
+}
+
+/// Finds the target with the given name.
+pub fn find_target<'a>(targets: &'a [Target], name: &str) -> Result<&'a Target> {
+    let mut matches = targets.iter().filter(|target| target.name == name);
+    let target = matches.next().ok_or_else(|| {
+        anyhow!(
+            "no propfuzz target named '{}' (run `cargo propfuzz list` to see all targets)",
+            name
+        )
+    })?;
+    if let Some(other) = matches.next() {
+        bail!(
+            "propfuzz target '{}' is present in both {} and {} (use --lib or --test to pick one)",
+            name,
+            target.binary.display(),
+            other.binary.display(),
+        );
+    }
+    Ok(target)
+}
+
+/// Returns true if the test binary defines the driver test through `propfuzz::driver!`.
+fn has_driver(binary: &TestBinary) -> Result<bool> {
+    let output = test_command(&binary.path, &binary.package_dir)
+        .arg("--list")
+        .stderr(Stdio::inherit())
+        .output()
+        .with_context(|| format!("failed to run {}", binary.path.display()))?;
+    if !output.status.success() {
+        bail!("failed to list tests in {}", binary.path.display());
+    }
+    let expected = format!("{}: test", driver::TEST_NAME);
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .any(|line| line == expected))
+}
+
+fn driver_command(binary: &Path, package_dir: &Path, command: &str) -> Command {
+    // Only run the driver test, and let it print directly to stdout and stderr. --quiet keeps the
+    // test harness from printing the name of the test on the same line as the output.
+    let mut cmd = test_command(binary, package_dir);
+    cmd.arg(driver::TEST_NAME)
+        .arg("--exact")
+        .arg("--nocapture")
+        .arg("--quiet")
+        .env(driver::DRIVER_ENV, command);
+    cmd
+}
+
+fn test_command(binary: &Path, package_dir: &Path) -> Command {
+    // Run test binaries the same way cargo test does.
+    let mut cmd = Command::new(binary);
+    cmd.current_dir(package_dir)
+        .env("CARGO_MANIFEST_DIR", package_dir);
+    cmd
+}
@@ -0,0 +1,299 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! A cargo subcommand to list, test and fuzz propfuzz targets.
+//!
+//! `cargo propfuzz` builds the test binaries for a crate through `cargo test --no-run`, then finds
+//! every `#[propfuzz]` target linked into them. Targets can then be run as standard property-based
+//! tests, or under an in-process fuzz loop. No separate fuzz crates need to be written.
+//!
+//! Each test binary needs to invoke `propfuzz::driver!()` once at its crate root, which defines the
+//! test that `cargo propfuzz` runs targets through. Binaries without it are skipped.
+//!
+//! ## Usage
+//!
+//! ```text
+//! cargo install cargo-propfuzz
+//!
+//! # List all propfuzz targets, along with their descriptions.
+//! cargo propfuzz list
+//!
+//! # Run a target as a property-based test.
+//! cargo propfuzz test my_crate::my_module::my_test
+//!
+//! # Fuzz a target for 60 seconds.
+//! cargo propfuzz fuzz my_crate::my_module::my_test --max-total-time 60
+//!
+//! # Fuzz every target with `fuzz_default = true` for 60 seconds each. Pass in --all to fuzz
+//! # all targets instead.
+//! cargo propfuzz fuzz --max-total-time 60
+//!
+//! # Fuzz a target with coverage feedback, which requires the `coverage` feature of propfuzz.
+//! cargo propfuzz fuzz my_crate::my_module::my_test --coverage
+//!
+//! # Replay a crash found by a fuzzer, printing the failing value.
+//! cargo propfuzz replay my_crate::my_module::my_test crash-0123456789abcdef
+//!
+//! # Shrink a crash found by a fuzzer to a minimal failing value.
+//! cargo propfuzz shrink my_crate::my_module::my_test crash-0123456789abcdef
+//!
+//! # Generate a seed corpus for a target.
+//! cargo propfuzz corpus my_crate::my_module::my_test --count 1000
+//! ```
+//!
+//! Common `cargo test` options, such as `--package`, `--test`, `--features` and `--release`, are
+//! passed through when building test binaries. Run `cargo propfuzz help <subcommand>` for the full
+//! list of options.
+
+mod harness;
+
+use crate::harness::BuildOpts;
+use anyhow::{bail, Context, Result};
+use propfuzz::driver;
+use std::env;
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::process;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(bin_name = "cargo")]
+enum Cargo {
+    /// List, test and fuzz propfuzz targets.
+    Propfuzz(Command),
+}
+
+#[derive(Debug, StructOpt)]
+enum Command {
+    /// List all propfuzz targets.
+    List {
+        #[structopt(flatten)]
+        build: BuildOpts,
+    },
+    /// Run a propfuzz target as a standard property-based test.
+    Test {
+        /// The name of the target, as printed by `cargo propfuzz list`.
+        name: String,
+        #[structopt(flatten)]
+        build: BuildOpts,
+    },
+    /// Run propfuzz targets under an in-process fuzz loop.
+    ///
+    /// By default, every target with `fuzz_default = true` is fuzzed in turn.
+    Fuzz {
+        /// The name of a single target to fuzz, as printed by `cargo propfuzz list`.
+        name: Option<String>,
+        /// Fuzz all targets, not just the ones with `fuzz_default = true`.
+        #[structopt(long, conflicts_with = "name")]
+        all: bool,
+        /// Maximum number of inputs to run.
+        #[structopt(long)]
+        runs: Option<u64>,
+        /// Maximum number of seconds to fuzz for.
+        #[structopt(long)]
+        max_total_time: Option<u64>,
+        /// Maximum length of a generated input, in bytes.
+        #[structopt(long)]
+        max_len: Option<usize>,
+        /// Seed for generating inputs (defaults to a time-based seed).
+        #[structopt(long)]
+        seed: Option<u64>,
+        /// Directory to write failing inputs to [default: propfuzz-artifacts in the package directory].
+        #[structopt(long, parse(from_os_str))]
+        artifact_dir: Option<PathBuf>,
+        /// Build with coverage instrumentation, and use coverage feedback to guide fuzzing.
+        ///
+        /// Requires the `coverage` feature of propfuzz to be enabled.
+        #[structopt(long)]
+        coverage: bool,
+        #[structopt(flatten)]
+        build: BuildOpts,
+    },
+    /// Replay an input, such as a crash found by a fuzzer, against a propfuzz target.
+    Replay {
+        /// The name of the target, as printed by `cargo propfuzz list`.
+        name: String,
+        /// The file containing the input.
+        #[structopt(parse(from_os_str))]
+        input: PathBuf,
+        #[structopt(flatten)]
+        build: BuildOpts,
+    },
+    /// Shrink a failing input, such as a crash found by a fuzzer, to a minimal failing value.
+    Shrink {
+        /// The name of the target, as printed by `cargo propfuzz list`.
+        name: String,
+        /// The file containing the input.
+        #[structopt(parse(from_os_str))]
+        input: PathBuf,
+        #[structopt(flatten)]
+        build: BuildOpts,
+    },
+    /// Generate a seed corpus for a propfuzz target.
+    Corpus {
+        /// The name of the target, as printed by `cargo propfuzz list`.
+        name: String,
+        /// Directory to write the corpus to [default: propfuzz-corpus/<name> in the package
+        /// directory].
+        #[structopt(long, parse(from_os_str))]
+        dir: Option<PathBuf>,
+        /// Number of inputs to generate.
+        #[structopt(long, default_value = "256")]
+        count: usize,
+        #[structopt(flatten)]
+        build: BuildOpts,
+    },
+}
+
+impl Command {
+    fn exec(self) -> Result<i32> {
+        match self {
+            Command::List { build } => {
+                let binaries = harness::build(&build)?;
+                for target in harness::list_targets(&binaries)? {
+                    if target.fuzz_default {
+                        println!("{} (fuzz default)", target.name);
+                    } else {
+                        println!("{}", target.name);
+                    }
+                    if !target.description.is_empty() {
+                        println!("    {}", target.description);
+                    }
+                }
+                Ok(0)
+            }
+            Command::Test { name, build } => {
+                let binaries = harness::build(&build)?;
+                let targets = harness::list_targets(&binaries)?;
+                let target = harness::find_target(&targets, &name)?;
+                target.run("test", vec![])
+            }
+            Command::Fuzz {
+                name,
+                all,
+                runs,
+                max_total_time,
+                max_len,
+                seed,
+                artifact_dir,
+                coverage,
+                mut build,
+            } => {
+                build.coverage = coverage;
+                let binaries = harness::build(&build)?;
+                let targets = harness::list_targets(&binaries)?;
+                let selected: Vec<_> = match &name {
+                    Some(name) => vec![harness::find_target(&targets, name)?],
+                    None if all => targets.iter().collect(),
+                    None => targets
+                        .iter()
+                        .filter(|target| target.fuzz_default)
+                        .collect(),
+                };
+                if selected.is_empty() {
+                    bail!(
+                        "no targets to fuzz (set `fuzz_default = true` on some targets, or pass in \
+                         a target name or --all)"
+                    );
+                }
+                if selected.len() > 1 && runs.is_none() && max_total_time.is_none() {
+                    bail!("fuzzing more than one target requires --runs or --max-total-time");
+                }
+
+                let mut envs: Vec<(&str, OsString)> = vec![];
+                if let Some(runs) = runs {
+                    envs.push((driver::RUNS_ENV, runs.to_string().into()));
+                }
+                if let Some(max_total_time) = max_total_time {
+                    envs.push((
+                        driver::MAX_TOTAL_TIME_ENV,
+                        max_total_time.to_string().into(),
+                    ));
+                }
+                if let Some(max_len) = max_len {
+                    envs.push((driver::MAX_LEN_ENV, max_len.to_string().into()));
+                }
+                if let Some(seed) = seed {
+                    envs.push((driver::SEED_ENV, seed.to_string().into()));
+                }
+
+                let artifact_dir = artifact_dir.map(absolute).transpose()?;
+                let mut failed = vec![];
+                for target in &selected {
+                    let artifact_dir = artifact_dir
+                        .clone()
+                        .unwrap_or_else(|| target.package_dir.join("propfuzz-artifacts"));
+                    let mut envs = envs.clone();
+                    envs.push((driver::ARTIFACT_DIR_ENV, artifact_dir.into()));
+                    if target.run("fuzz", envs)? != 0 {
+                        failed.push(target.name.as_str());
+                    }
+                }
+
+                if failed.is_empty() {
+                    Ok(0)
+                } else {
+                    if selected.len() > 1 {
+                        eprintln!("error: failures found in: {}", failed.join(", "));
+                    }
+                    Ok(1)
+                }
+            }
+            Command::Replay { name, input, build } => replay("replay", &name, input, &build),
+            Command::Shrink { name, input, build } => replay("shrink", &name, input, &build),
+            Command::Corpus {
+                name,
+                dir,
+                count,
+                build,
+            } => {
+                let binaries = harness::build(&build)?;
+                let targets = harness::list_targets(&binaries)?;
+                let target = harness::find_target(&targets, &name)?;
+
+                let dir = match dir {
+                    Some(dir) => absolute(dir)?,
+                    None => target
+                        .package_dir
+                        .join("propfuzz-corpus")
+                        .join(target.name.replace("::", "__")),
+                };
+                let envs: Vec<(&str, OsString)> = vec![
+                    (driver::CORPUS_DIR_ENV, dir.into()),
+                    (driver::COUNT_ENV, count.to_string().into()),
+                ];
+                target.run("corpus", envs)
+            }
+        }
+    }
+}
+
+fn replay(command: &str, name: &str, input: PathBuf, build: &BuildOpts) -> Result<i32> {
+    let input = absolute(input)?;
+
+    let binaries = harness::build(build)?;
+    let targets = harness::list_targets(&binaries)?;
+    let target = harness::find_target(&targets, name)?;
+    target.run(command, vec![(driver::INPUT_ENV, input.into())])
+}
+
+/// Resolves a path passed in on the command line against the current directory.
+///
+/// Test binaries run in the package directory, so relative paths passed on to them would otherwise
+/// be resolved against that.
+fn absolute(path: PathBuf) -> Result<PathBuf> {
+    Ok(env::current_dir()
+        .context("failed to get current directory")?
+        .join(path))
+}
+
+fn main() {
+    let Cargo::Propfuzz(command) = Cargo::from_args();
+    match command.exec() {
+        Ok(code) => process::exit(code),
+        Err(err) => {
+            eprintln!("error: {:#}", err);
+            process::exit(1);
+        }
+    }
+}
@@ -4,10 +4,14 @@
 //! Configuration for propfuzz macros.
 
 use crate::errors::{Error, ErrorList, Result};
-use proc_macro2::{Span, TokenStream};
+use proc_macro2::{Ident, Span, TokenStream};
 use quote::{quote, ToTokens};
 use syn::punctuated::Punctuated;
-use syn::{spanned::Spanned, Attribute, Expr, Lit, Meta, MetaNameValue, NestedMeta, Token, Type};
+use syn::{
+    spanned::Spanned, Attribute, Expr, ExprArray, ExprLit, ExprPath, ExprRange, GenericArgument,
+    Lit, LitStr, Meta, MetaNameValue, NestedMeta, PathArguments, PathSegment, RangeLimits, Token,
+    Type,
+};
 
 // ---
 // Config builders
@@ -63,6 +67,11 @@ pub(crate) trait ConfigBuilder {
 #[derive(Debug, Default)]
 pub(crate) struct PropfuzzConfigBuilder {
     fuzz_default: Option<bool>,
+    corpus_generator: Option<Expr>,
+    examples: Vec<Expr>,
+    runtime: Option<(AsyncRuntime, Span)>,
+    types: Vec<TypeParamValues>,
+    params: Vec<FixedParamValues>,
     proptest: ProptestConfig,
 }
 
@@ -71,6 +80,11 @@ impl PropfuzzConfigBuilder {
     pub(crate) fn finish(self) -> PropfuzzConfig {
         PropfuzzConfig {
             fuzz_default: self.fuzz_default.unwrap_or(false),
+            corpus_generator: self.corpus_generator,
+            examples: self.examples,
+            runtime: self.runtime,
+            types: self.types,
+            params: self.params,
             proptest: self.proptest,
         }
     }
@@ -84,6 +98,50 @@ impl ConfigBuilder for PropfuzzConfigBuilder {
             errors.combine_fn(|| {
                 replace_empty(meta.span(), &mut self.fuzz_default, read_bool(meta)?)
             });
+        } else if path.is_ident("corpus_generator") {
+            errors.combine_fn(|| {
+                replace_empty(meta.span(), &mut self.corpus_generator, read_expr(meta)?)
+            });
+        } else if path.is_ident("example") {
+            // Examples are repeatable, so don't use replace_empty.
+            errors.combine_fn(|| {
+                self.examples.push(read_expr(meta)?);
+                Ok(())
+            });
+        } else if path.is_ident("runtime") {
+            errors.combine_fn(|| {
+                replace_empty(
+                    meta.span(),
+                    &mut self.runtime,
+                    (read_runtime(meta)?, meta.span()),
+                )
+            });
+        } else if path.is_ident("types") {
+            errors.combine_fn(|| {
+                for values in read_types(meta)? {
+                    if self.types.iter().any(|other| other.param == values.param) {
+                        return Err(Error::new_spanned(
+                            &values.param,
+                            "type parameter specified more than once",
+                        ));
+                    }
+                    self.types.push(values);
+                }
+                Ok(())
+            });
+        } else if path.is_ident("params") {
+            errors.combine_fn(|| {
+                for values in read_params(meta)? {
+                    if self.params.iter().any(|other| other.param == values.param) {
+                        return Err(Error::new_spanned(
+                            &values.param,
+                            "argument specified more than once",
+                        ));
+                    }
+                    self.params.push(values);
+                }
+                Ok(())
+            });
         } else if path.is_ident("cases") {
             errors.combine_fn(|| {
                 replace_empty(meta.span(), &mut self.proptest.cases, read_u32(meta)?)
@@ -140,6 +198,14 @@ impl ConfigBuilder for PropfuzzConfigBuilder {
             errors.combine_fn(|| {
                 replace_empty(meta.span(), &mut self.proptest.verbose, read_u32(meta)?)
             });
+        } else if path.is_ident("persistence") {
+            errors.combine_fn(|| {
+                replace_empty(
+                    meta.span(),
+                    &mut self.proptest.persistence,
+                    read_persistence(meta)?,
+                )
+            });
         } else {
             errors.combine(Error::new_spanned(path, "argument not recognized"));
         }
@@ -149,11 +215,45 @@ impl ConfigBuilder for PropfuzzConfigBuilder {
 /// Overall config for a single propfuzz function, fully built.
 #[derive(Debug)]
 pub(crate) struct PropfuzzConfig {
-    // fuzz_default is currently unused.
-    fuzz_default: bool,
+    pub(crate) fuzz_default: bool,
+    pub(crate) corpus_generator: Option<Expr>,
+    pub(crate) examples: Vec<Expr>,
+    pub(crate) runtime: Option<(AsyncRuntime, Span)>,
+    pub(crate) types: Vec<TypeParamValues>,
+    pub(crate) params: Vec<FixedParamValues>,
     pub(crate) proptest: ProptestConfig,
 }
 
+/// The executor that runs `async fn` targets.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum AsyncRuntime {
+    Builtin,
+    Tokio,
+}
+
+impl ToTokens for AsyncRuntime {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.extend(match self {
+            AsyncRuntime::Builtin => quote! { ::propfuzz::executor::block_on },
+            AsyncRuntime::Tokio => quote! { ::propfuzz::__tokio_block_on! },
+        })
+    }
+}
+
+/// The concrete types to instantiate a generic type parameter with, from `types(T = "...")`.
+#[derive(Debug)]
+pub(crate) struct TypeParamValues {
+    pub(crate) param: Ident,
+    pub(crate) types: Vec<Type>,
+}
+
+/// The fixed values for an argument, from `params(name = "[...]")`. Each value is a table row.
+#[derive(Debug)]
+pub(crate) struct FixedParamValues {
+    pub(crate) param: Ident,
+    pub(crate) values: Vec<Expr>,
+}
+
 /// Proptest config for a single propfuzz function.
 ///
 /// This contains most of the settings in proptest's config.
@@ -168,6 +268,41 @@ pub(crate) struct ProptestConfig {
     max_shrink_time: Option<u32>,
     max_shrink_iters: Option<u32>,
     verbose: Option<u32>,
+    persistence: Option<Persistence>,
+}
+
+/// Where failing seeds are persisted.
+#[derive(Debug)]
+pub(crate) enum Persistence {
+    Off,
+    WithSource,
+    SourceParallel,
+    Directory(LitStr),
+}
+
+impl ToTokens for Persistence {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let persistence = match self {
+            Persistence::Off => return tokens.extend(quote! { None }),
+            Persistence::WithSource => quote! {
+                ::propfuzz::proptest::test_runner::FileFailurePersistence::WithSource(
+                    "proptest-regressions",
+                )
+            },
+            Persistence::SourceParallel => quote! {
+                ::propfuzz::proptest::test_runner::FileFailurePersistence::SourceParallel(
+                    "proptest-regressions",
+                )
+            },
+            Persistence::Directory(dir) => quote! {
+                ::propfuzz::persistence::DirectoryPersistence::new(
+                    ::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(#dir),
+                    ::propfuzz::traits::StructuredTarget::name(self),
+                )
+            },
+        };
+        tokens.extend(quote! { Some(::std::boxed::Box::new(#persistence)) })
+    }
 }
 
 macro_rules! extend_config {
@@ -193,10 +328,14 @@ impl ToTokens for ProptestConfig {
             max_shrink_time,
             max_shrink_iters,
             verbose,
+            persistence,
         } = self;
 
         tokens.extend(quote! {
-            let mut config = ::propfuzz::proptest::test_runner::Config::default();
+            // Start off from the values in propfuzz.toml, if any.
+            let mut config = ::propfuzz::config::base_config(
+                ::propfuzz::traits::StructuredTarget::name(self),
+            );
             config.source_file = Some(file!());
         });
 
@@ -210,39 +349,152 @@ impl ToTokens for ProptestConfig {
         extend_config!(tokens, max_shrink_iters);
         extend_config!(tokens, verbose);
 
+        let persistence = persistence.as_ref().unwrap_or(&Persistence::WithSource);
+        tokens.extend(quote! {
+            config.failure_persistence = #persistence;
+        });
+
         tokens.extend(quote! { config })
     }
 }
 
+// ---
+// Config for a suite
+// ---
+
+/// Configuration for a propfuzz suite.
+#[derive(Debug, Default)]
+pub(crate) struct SuiteConfigBuilder {
+    fixture: Option<Expr>,
+    impls: Option<(Vec<Type>, Span)>,
+}
+
+impl SuiteConfigBuilder {
+    /// Completes building args and returns a `SuiteConfig`.
+    pub(crate) fn finish(self) -> SuiteConfig {
+        SuiteConfig {
+            fixture: self.fixture,
+            impls: self.impls,
+        }
+    }
+}
+
+impl ConfigBuilder for SuiteConfigBuilder {
+    fn apply_meta(&mut self, meta: &Meta, errors: &mut ErrorList) {
+        let path = meta.path();
+        if path.is_ident("fixture") {
+            errors.combine_fn(|| replace_empty(meta.span(), &mut self.fixture, read_expr(meta)?));
+        } else if path.is_ident("for") {
+            errors.combine_fn(|| {
+                replace_empty(
+                    meta.span(),
+                    &mut self.impls,
+                    (read_type_list(meta)?, meta.span()),
+                )
+            });
+        } else {
+            errors.combine(Error::new_spanned(path, "argument not recognized"));
+        }
+    }
+}
+
+/// Overall config for a propfuzz suite, fully built.
+#[derive(Debug)]
+pub(crate) struct SuiteConfig {
+    // The expression that fixtures are built with, for impls. Defaults to `Default::default()`.
+    pub(crate) fixture: Option<Expr>,
+    // The implementations to instantiate functions with, for modules.
+    pub(crate) impls: Option<(Vec<Type>, Span)>,
+}
+
 // ---
 // Configuration for arguments
 // ---
 
 pub(crate) struct ParamConfigBuilder<'a> {
     ty: &'a Type,
-    strategy: Option<Expr>,
+    // The strategy, along with the key it was specified through: either strategy or one of the
+    // shorthands for it.
+    strategy: Option<(&'static str, TokenStream)>,
+    filter: Option<ExprPath>,
 }
 
 impl<'a> ParamConfigBuilder<'a> {
     pub(crate) fn new(ty: &'a Type) -> Self {
-        Self { ty, strategy: None }
+        Self {
+            ty,
+            strategy: None,
+            filter: None,
+        }
     }
 
     pub(crate) fn finish(self) -> ParamConfig {
         let ty = self.ty;
         let strategy = match self.strategy {
-            Some(expr) => quote! { #expr },
+            Some((_, strategy)) => strategy,
             None => quote! { ::propfuzz::proptest::arbitrary::any::<#ty>() },
         };
+        let strategy = match self.filter {
+            Some(filter) => quote! {
+                ::propfuzz::proptest::strategy::Strategy::prop_filter(
+                    #strategy,
+                    concat!("filter = ", stringify!(#filter)),
+                    |value: &#ty| #filter(value),
+                )
+            },
+            None => strategy,
+        };
         ParamConfig { strategy }
     }
+
+    /// Sets the strategy, which can only be specified through one key.
+    fn set_strategy(
+        &mut self,
+        meta: &Meta,
+        key: &'static str,
+        strategy: TokenStream,
+    ) -> Result<()> {
+        match &self.strategy {
+            Some((other, _)) if *other == key => {
+                Err(Error::new(meta.span(), "key specified more than once"))
+            }
+            Some(_) => Err(Error::new_spanned(
+                meta.path(),
+                "only one of strategy, range, regex and len can be specified",
+            )),
+            None => {
+                self.strategy = Some((key, strategy));
+                Ok(())
+            }
+        }
+    }
 }
 
 impl<'a> ConfigBuilder for ParamConfigBuilder<'a> {
     fn apply_meta(&mut self, meta: &Meta, errors: &mut ErrorList) {
         let path = meta.path();
         if path.is_ident("strategy") {
-            errors.combine_fn(|| replace_empty(meta.span(), &mut self.strategy, read_expr(meta)?));
+            errors.combine_fn(|| {
+                let expr = read_expr(meta)?;
+                self.set_strategy(meta, "strategy", quote! { #expr })
+            });
+        } else if path.is_ident("range") {
+            errors.combine_fn(|| {
+                let strategy = read_range(meta, self.ty)?;
+                self.set_strategy(meta, "range", strategy)
+            });
+        } else if path.is_ident("regex") {
+            errors.combine_fn(|| {
+                let strategy = read_regex(meta, self.ty)?;
+                self.set_strategy(meta, "regex", strategy)
+            });
+        } else if path.is_ident("len") {
+            errors.combine_fn(|| {
+                let strategy = read_len(meta, self.ty)?;
+                self.set_strategy(meta, "len", strategy)
+            });
+        } else if path.is_ident("filter") {
+            errors.combine_fn(|| replace_empty(meta.span(), &mut self.filter, read_filter(meta)?));
         } else {
             errors.combine(Error::new_spanned(path, "argument not recognized"));
         }
@@ -291,6 +543,288 @@ fn read_expr(meta: &Meta) -> Result<Expr> {
     }
 }
 
+/// Reads `range = "0..100"`, which generates values within the range.
+fn read_range(meta: &Meta, ty: &Type) -> Result<TokenStream> {
+    let lit = read_str(meta, "expected range string")?;
+    let range = lit.parse::<Expr>()?;
+    // Literals in the range aren't inferred to be of the argument's type through the strategy, so
+    // spell out the type of the range.
+    let range_ty = match &range {
+        Expr::Range(ExprRange {
+            from, limits, to, ..
+        }) => match (from, limits, to) {
+            (Some(_), RangeLimits::HalfOpen(_), Some(_)) => quote! { Range },
+            (Some(_), RangeLimits::Closed(_), Some(_)) => quote! { RangeInclusive },
+            (Some(_), _, None) => quote! { RangeFrom },
+            (None, RangeLimits::HalfOpen(_), Some(_)) => quote! { RangeTo },
+            (None, RangeLimits::Closed(_), Some(_)) => quote! { RangeToInclusive },
+            (None, _, None) => {
+                return Err(Error::new_spanned(
+                    lit,
+                    "expected a range with at least one bound",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                lit,
+                "expected a range such as \"0..100\"",
+            ))
+        }
+    };
+    match last_segment(ty) {
+        Some(segment) if NUMERIC_TYPES.iter().any(|name| segment.ident == name) => Ok(quote! {
+            {
+                let range: ::std::ops::#range_ty<#ty> = #range;
+                range
+            }
+        }),
+        _ => Err(Error::new_spanned(
+            meta.path(),
+            "range requires a numeric type such as u32 or f64",
+        )),
+    }
+}
+
+const NUMERIC_TYPES: &[&str] = &[
+    "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize", "f32",
+    "f64",
+];
+
+/// Reads `regex = "[a-z]+"`, which generates strings matching the regex.
+fn read_regex(meta: &Meta, ty: &Type) -> Result<TokenStream> {
+    let lit = read_str(meta, "expected regex string")?;
+    if let Err(err) = regex_syntax::Parser::new().parse(&lit.value()) {
+        let message = match &err {
+            regex_syntax::Error::Parse(err) => err.kind().to_string(),
+            regex_syntax::Error::Translate(err) => err.kind().to_string(),
+            err => err.to_string(),
+        };
+        return Err(Error::new_spanned(
+            lit,
+            format!("invalid regex: {}", message),
+        ));
+    }
+    match last_segment(ty) {
+        Some(segment) if segment.ident == "String" && segment.arguments.is_empty() => Ok(quote! {
+            ::propfuzz::proptest::string::string_regex(#lit)
+                .expect(concat!("regex not supported by proptest: ", #lit))
+        }),
+        _ => Err(Error::new_spanned(meta.path(), "regex requires a String")),
+    }
+}
+
+/// Reads `len = "0..64"`, which generates collections with a length in the range, and elements
+/// through their canonical strategy.
+fn read_len(meta: &Meta, ty: &Type) -> Result<TokenStream> {
+    let lit = read_str(meta, "expected length string")?;
+    let len = lit.parse::<Expr>()?;
+    if !matches!(
+        len,
+        Expr::Range(_)
+            | Expr::Lit(ExprLit {
+                lit: Lit::Int(_),
+                ..
+            })
+    ) {
+        return Err(Error::new_spanned(
+            lit,
+            "expected a length or a range of lengths such as \"0..64\"",
+        ));
+    }
+
+    let error = || {
+        Error::new_spanned(
+            meta.path(),
+            "len requires a collection type such as Vec<T> or HashMap<K, V>",
+        )
+    };
+    let segment = last_segment(ty).ok_or_else(error)?;
+    let args: Vec<_> = match &segment.arguments {
+        PathArguments::AngleBracketed(args) => args
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => return Err(error()),
+    };
+    let (function, elements) = match (
+        COLLECTIONS
+            .iter()
+            .find(|(name, _, _)| segment.ident == name),
+        args.as_slice(),
+    ) {
+        (Some((_, function, 1)), [elem]) => (function, vec![*elem]),
+        (Some((_, function, 2)), [key, value, ..]) => (function, vec![*key, *value]),
+        _ => return Err(error()),
+    };
+    let function = Ident::new(function, Span::call_site());
+    Ok(quote! {
+        ::propfuzz::proptest::collection::#function(
+            #(::propfuzz::proptest::arbitrary::any::<#elements>(),)*
+            #len
+        )
+    })
+}
+
+/// Collection types supported by len, along with the function in `proptest::collection` for
+/// each and the number of arguments that have strategies.
+const COLLECTIONS: &[(&str, &str, usize)] = &[
+    ("Vec", "vec", 1),
+    ("VecDeque", "vec_deque", 1),
+    ("LinkedList", "linked_list", 1),
+    ("BinaryHeap", "binary_heap", 1),
+    ("HashSet", "hash_set", 1),
+    ("BTreeSet", "btree_set", 1),
+    ("HashMap", "hash_map", 2),
+    ("BTreeMap", "btree_map", 2),
+];
+
+/// Reads `filter = "path::to::pred"`, which only keeps values the predicate returns true for.
+fn read_filter(meta: &Meta) -> Result<ExprPath> {
+    let lit = read_str(meta, "expected path string")?;
+    lit.parse::<ExprPath>().map_err(|_| {
+        Error::new_spanned(
+            lit,
+            "expected a path to a function such as \"path::to::pred\"",
+        )
+    })
+}
+
+fn read_persistence(meta: &Meta) -> Result<Persistence> {
+    let name_value = name_value(meta)?;
+    match &name_value.lit {
+        Lit::Str(lit) => match lit.value().as_str() {
+            "off" => Ok(Persistence::Off),
+            "with-source" => Ok(Persistence::WithSource),
+            "source-parallel" => Ok(Persistence::SourceParallel),
+            "" => Err(Error::new_spanned(
+                lit,
+                "expected \"off\", \"with-source\", \"source-parallel\" or a directory",
+            )),
+            _ => Ok(Persistence::Directory(lit.clone())),
+        },
+        _ => Err(Error::new_spanned(
+            &name_value.lit,
+            "expected persistence string",
+        )),
+    }
+}
+
+fn read_runtime(meta: &Meta) -> Result<AsyncRuntime> {
+    let name_value = name_value(meta)?;
+    match &name_value.lit {
+        Lit::Str(lit) => match lit.value().as_str() {
+            "builtin" => Ok(AsyncRuntime::Builtin),
+            "tokio" => Ok(AsyncRuntime::Tokio),
+            _ => Err(Error::new_spanned(lit, "expected \"builtin\" or \"tokio\"")),
+        },
+        _ => Err(Error::new_spanned(
+            &name_value.lit,
+            "expected runtime string",
+        )),
+    }
+}
+
+fn read_types(meta: &Meta) -> Result<Vec<TypeParamValues>> {
+    read_name_values(meta, "types(T = \"type, ...\")", |param, lit| {
+        Ok(TypeParamValues {
+            param: param.clone(),
+            types: parse_types(lit)?,
+        })
+    })
+}
+
+fn read_type_list(meta: &Meta) -> Result<Vec<Type>> {
+    let name_value = name_value(meta)?;
+    match &name_value.lit {
+        Lit::Str(lit) => parse_types(lit),
+        _ => Err(Error::new_spanned(&name_value.lit, "expected types string")),
+    }
+}
+
+/// Parses a comma-separated list of types, such as `"u8, u32"`.
+fn parse_types(lit: &LitStr) -> Result<Vec<Type>> {
+    let types = lit.parse_with(Punctuated::<Type, Token![,]>::parse_terminated)?;
+    if types.is_empty() {
+        return Err(Error::new_spanned(lit, "expected at least one type"));
+    }
+    Ok(types.into_iter().collect())
+}
+
+fn read_params(meta: &Meta) -> Result<Vec<FixedParamValues>> {
+    read_name_values(meta, "params(name = \"[value, ...]\")", |param, lit| {
+        let array = lit.parse::<ExprArray>()?;
+        if array.elems.is_empty() {
+            return Err(Error::new_spanned(lit, "expected at least one value"));
+        }
+        Ok(FixedParamValues {
+            param: param.clone(),
+            values: array.elems.into_iter().collect(),
+        })
+    })
+}
+
+/// Reads a list of `name = "..."` pairs, such as `types(T = "u8, u32")`.
+fn read_name_values<T>(
+    meta: &Meta,
+    format: &str,
+    mut read: impl FnMut(&Ident, &LitStr) -> Result<T>,
+) -> Result<Vec<T>> {
+    let list = match meta {
+        Meta::List(list) => list,
+        _ => {
+            return Err(Error::new_spanned(
+                meta,
+                format!("expected {} format", format),
+            ))
+        }
+    };
+
+    let mut errors = ErrorList::new();
+    let values = list
+        .nested
+        .iter()
+        .filter_map(|nested| {
+            errors.combine_opt(|| match nested {
+                NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                    path,
+                    lit: Lit::Str(lit),
+                    ..
+                })) => match path.get_ident() {
+                    Some(name) => read(name, lit),
+                    None => Err(Error::new_spanned(path, "expected a name")),
+                },
+                _ => Err(Error::new_spanned(
+                    nested,
+                    format!("expected {} format", format),
+                )),
+            })
+        })
+        .collect();
+    errors.finish()?;
+    Ok(values)
+}
+
+fn read_str<'a>(meta: &'a Meta, message: &str) -> Result<&'a LitStr> {
+    let name_value = name_value(meta)?;
+    match &name_value.lit {
+        Lit::Str(lit) => Ok(lit),
+        _ => Err(Error::new_spanned(&name_value.lit, message)),
+    }
+}
+
+/// Returns the last segment of a path type, such as `Vec<u8>` for `std::vec::Vec<u8>`.
+fn last_segment(ty: &Type) -> Option<&PathSegment> {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last(),
+        _ => None,
+    }
+}
+
 fn name_value(meta: &Meta) -> Result<&MetaNameValue> {
     match meta {
         Meta::NameValue(meta) => Ok(meta),
@@ -9,17 +9,18 @@
 extern crate proc_macro;
 
 use proc_macro::TokenStream;
-use syn::{parse_macro_input, AttributeArgs, ItemFn};
+use syn::{parse_macro_input, AttributeArgs, Item, ItemFn};
 
 mod config;
 mod errors;
 mod propfuzz_impl;
+mod suite_impl;
 
 /// The core macro, used to annotate test methods.
 ///
 /// Annotate a function with this in order to have it run as a property-based test using the
-/// [`proptest`](https://docs.rs/proptest) framework. In the future, such tests will also be
-/// available as fuzz targets.
+/// [`proptest`](https://docs.rs/proptest) framework. Such tests can also be run as fuzz targets
+/// through `propfuzz::runtime::execute_as_fuzz_target`.
 ///
 /// # Examples
 ///
@@ -42,6 +43,117 @@ mod propfuzz_impl;
 /// }
 /// ```
 ///
+/// # Unstructured targets
+///
+/// A function that takes a single `&[u8]` or `&str` argument is an *unstructured* target. When
+/// fuzzing, it receives fuzzer input directly rather than values generated from it (inputs that
+/// aren't valid UTF-8 are skipped for `&str`). As a property-based test, it receives values
+/// generated by `any::<Vec<u8>>()` or `any::<String>()`, unless a `strategy` is specified.
+///
+/// Unstructured arguments can't be combined with other arguments.
+///
+/// ```
+/// use propfuzz::prelude::*;
+///
+/// /// Parsing arbitrary bytes as UTF-8 doesn't panic.
+/// #[propfuzz]
+/// fn parse_utf8(data: &[u8]) {
+///     let _ = std::str::from_utf8(data);
+/// }
+/// ```
+///
+/// # Generic functions
+///
+/// Generic functions are instantiated with the types listed through `types`, with one target
+/// and one test per instantiation. Functions with several type parameters are instantiated with
+/// every combination of types. Each target is named after its types, e.g. `roundtrip::<u8>`, and
+/// its test is named `roundtrip::u8`.
+///
+/// ```
+/// use propfuzz::prelude::*;
+/// use std::fmt::Debug;
+///
+/// /// Cloning a value produces an equal value.
+/// #[propfuzz(types(T = "u8, u32, String"))]
+/// fn clone_eq<T: Arbitrary + Clone + Debug + PartialEq>(value: T) {
+///     prop_assert_eq!(value.clone(), value);
+/// }
+/// ```
+///
+/// # Parameterized functions
+///
+/// Arguments can be fixed to each value in a table through `params`, rather than generated.
+/// Each value is an expression, and the table is written as an array. There is one target and one
+/// test per row, or per combination of rows if several arguments are fixed. Targets are named
+/// after their rows, e.g. `compress[mode=Fast]`, and their tests are named `compress::mode_Fast`.
+/// This can be combined with `types`.
+///
+/// Fixed arguments aren't part of the generated `Value`, including in `example` values.
+///
+/// ```
+/// use propfuzz::prelude::*;
+///
+/// #[derive(Clone, Copy, Debug)]
+/// enum Mode {
+///     Fast,
+///     Safe,
+/// }
+///
+/// /// Adding zero is a no-op in every mode.
+/// #[propfuzz(params(mode = "[Mode::Fast, Mode::Safe]"))]
+/// fn add_zero(mode: Mode, x: u32) {
+///     let sum = match mode {
+///         Mode::Fast => x.wrapping_add(0),
+///         Mode::Safe => x.checked_add(0).expect("no overflow"),
+///     };
+///     prop_assert_eq!(sum, x);
+/// }
+/// ```
+///
+/// # Returning errors
+///
+/// A function can return `Result<(), E>` for any `E` that implements `Display`, so that errors can
+/// be propagated with `?`. Returning `Err` fails the test, with the error as the reason. If `E` is
+/// `TestCaseError`, errors are passed through as is, so `prop_assert!` and `prop_assume!` work as
+/// usual. (Those macros return `TestCaseError`s, so they can't be used with other error types.)
+///
+/// ```
+/// use propfuzz::prelude::*;
+/// use std::num::ParseIntError;
+///
+/// /// Formatting and parsing a number round-trips.
+/// #[propfuzz]
+/// fn roundtrip(x: u64) -> Result<(), ParseIntError> {
+///     assert_eq!(x.to_string().parse::<u64>()?, x);
+///     Ok(())
+/// }
+/// ```
+///
+/// # Async functions
+///
+/// `async fn`s are supported as well. The body of the test is blocked on once per case, using
+/// the executor picked through the `runtime` argument:
+///
+/// * `"builtin"` (the default): a minimal executor that runs the future on the current thread.
+/// * `"tokio"`: a new single-threaded `tokio` runtime per case. Requires the `tokio` feature of
+///   `propfuzz`.
+///
+/// See `propfuzz::executor`.
+///
+/// ```
+/// use propfuzz::prelude::*;
+///
+/// async fn echo(data: Vec<u8>) -> Vec<u8> {
+///     data
+/// }
+///
+/// /// Echoing data returns it unchanged.
+/// #[propfuzz]
+/// async fn echo_roundtrip(data: Vec<u8>) {
+///     prop_assert_eq!(echo(data.clone()).await, data);
+/// }
+/// ```
+///
 /// # Arguments
 ///
 /// `propfuzz` supports a number of arguments which can be used to customize test behavior.
@@ -66,12 +178,22 @@ mod propfuzz_impl;
 /// }
 /// ```
 ///
-/// ## Fuzzing configuration
+/// ## Example inputs
 ///
-/// These arguments are currently unused but may be set. They will be used in the future once fuzzing support is
-/// available.
+/// * `example`: a known input that the test should always be run against, such as a past failure.
+///   The value must be a string that parses as a Rust expression of the tuple of argument types,
+///   e.g. `example = "(vec![0, 1],)"`, or of `Vec<u8>` or `String` for unstructured targets. May be
+///   repeated. Examples are run before any generated values, and fuzz drivers add them to their
+///   corpora where possible. See `propfuzz::traits::StructuredTarget::examples`.
+///
+/// ## Fuzzing configuration
 ///
-/// * `fuzz_default`: whether to fuzz this target by default. Defaults to `false`.
+/// * `fuzz_default`: whether to fuzz this target by default. Defaults to `false`. Fuzz drivers such as
+///   `cargo propfuzz fuzz` pick these targets if no target is named explicitly.
+/// * `corpus_generator`: a function that generates inputs for a seed corpus, for unstructured
+///   targets. The value must be a string that parses as a path to a function with the signature
+///   `fn(&mut ValueGenerator) -> Vec<u8>`. Defaults to generating inputs from the strategy for the
+///   argument. See `propfuzz::runtime::generate_corpus`.
 ///
 /// ## Proptest configuration
 ///
@@ -89,6 +211,18 @@ mod propfuzz_impl;
 /// * `max_shrink_iters`
 /// * `verbose`
 ///
+/// Failing seeds are saved and replayed first on the next run. Where they're saved is set through
+/// `persistence`, which is one of `"with-source"` (the default: a `.proptest-regressions` file next
+/// to the source file), `"source-parallel"`, `"off"`, or a directory relative to the crate root. See
+/// `propfuzz::persistence`.
+///
+/// Defaults for these options can be set in a `propfuzz.toml` file, which options set through the
+/// attribute take precedence over. See `propfuzz::config`.
+///
+/// Each of these can be overridden at runtime through environment variables such as
+/// `PROPFUZZ_CASES`, either for all targets or for a single one. See
+/// `propfuzz::runtime::apply_env_overrides`.
+///
 /// ## Argument configuration
 ///
 /// The following configuration options are supported on individual arguments:
@@ -98,7 +232,44 @@ mod propfuzz_impl;
 ///   [`Strategy`](https://docs.rs/proptest/0.10/proptest/strategy/trait.Strategy.html)
 ///   for the given type. Defaults to [the
 ///   canonical strategy](https://docs.rs/proptest/0.10/proptest/arbitrary/trait.Arbitrary.html)
-///   for the type.
+///   for the type. For unstructured arguments, the strategy must generate `Vec<u8>` or `String`
+///   values respectively.
+///
+/// Instead of `strategy`, one of these shorthands can be used, which are checked against the type
+/// of the argument:
+///
+/// * `range`: a range of values for numeric types, e.g. `range = "0..100"`.
+/// * `regex`: a regex that generated `String`s match, e.g. `regex = "[a-z]+"`.
+/// * `len`: the length or range of lengths for collections such as `Vec<T>` and `HashMap<K, V>`,
+///   e.g. `len = "0..64"`. Elements are generated through their canonical strategy.
+///
+/// Generated values can also be filtered:
+///
+/// * `filter`: a path to a function that takes a reference to a value and returns whether to keep
+///   it, e.g. `filter = "is_even"`. This applies on top of the strategy, or a shorthand for it.
+///
+/// ```
+/// use propfuzz::prelude::*;
+///
+/// fn is_sorted(list: &Vec<u8>) -> bool {
+///     list.windows(2).all(|pair| pair[0] <= pair[1])
+/// }
+///
+/// /// Looking up a value in a sorted list finds it.
+/// #[propfuzz]
+/// fn search(
+///     #[propfuzz(len = "1..4", filter = "is_sorted")] list: Vec<u8>,
+///     #[propfuzz(range = "0..16")] index: usize,
+/// ) {
+///     let value = list[index % list.len()];
+///     prop_assert!(list.binary_search(&value).is_ok());
+/// }
+/// ```
+///
+/// # Suites
+///
+/// `#[propfuzz]` can also be used on methods in an `impl`, or on functions in a module, annotated
+/// with [`#[propfuzz_suite]`](attr.propfuzz_suite.html).
 #[proc_macro_attribute]
 pub fn propfuzz(attr: TokenStream, item: TokenStream) -> TokenStream {
     let attr = parse_macro_input!(attr as AttributeArgs);
@@ -108,3 +279,127 @@ pub fn propfuzz(attr: TokenStream, item: TokenStream) -> TokenStream {
         .unwrap_or_else(|err| err)
         .into()
 }
+
+/// Runs `#[propfuzz]` methods in an `impl` block as tests with a shared fixture, or
+/// `#[propfuzz]` functions in a module against several implementations of a trait.
+///
+/// # Impl blocks
+///
+/// Each `#[propfuzz]` method in the `impl` becomes a target, named after the type and the method,
+/// e.g. `Fixture::method`. Its test is named `Fixture__method`. Methods that take `self` (by
+/// reference or by value) are called on a fixture that's built for every test case, so that
+/// setup code such as preparing a database schema can be shared between targets. Associated
+/// functions without `self` are supported as well.
+///
+/// The methods themselves are replaced with ones that return a `TestCaseResult`, so they can still
+/// be called directly. All the arguments of `#[propfuzz]` are supported.
+///
+/// The following arguments are supported:
+///
+/// * `fixture`: an expression for the fixture, which is evaluated for every test case. The value
+///   must be a string that parses as a Rust expression of type `Self`, e.g.
+///   `fixture = "Self::new(16)"`. Defaults to `Default::default()`.
+///
+/// ```
+/// use propfuzz::prelude::*;
+/// use std::collections::HashMap;
+///
+/// struct Dictionary {
+///     words: HashMap<String, u32>,
+/// }
+///
+/// impl Dictionary {
+///     fn with_words(words: &[&str]) -> Self {
+///         let words = words.iter().map(|word| (word.to_string(), 0)).collect();
+///         Self { words }
+///     }
+/// }
+///
+/// #[propfuzz_suite(fixture = "Dictionary::with_words(&[\"apple\", \"pear\"])")]
+/// impl Dictionary {
+///     /// Inserting a word makes it available.
+///     #[propfuzz]
+///     fn insert_get(&mut self, word: String, count: u32) {
+///         self.words.insert(word.clone(), count);
+///         prop_assert_eq!(self.words.get(&word), Some(&count));
+///     }
+///
+///     /// Words that weren't inserted aren't available.
+///     #[propfuzz]
+///     fn missing(&self, #[propfuzz(strategy = "\"[0-9]+\"")] word: String) {
+///         prop_assert!(!self.words.contains_key(&word));
+///     }
+/// }
+/// ```
+///
+/// # Modules
+///
+/// Properties can be written once against a trait and checked for every implementation of it.
+/// Each `#[propfuzz]` function in the module is instantiated with each implementation through its
+/// first type parameter, with one target per implementation. Targets are named after the module,
+/// the implementation and the function, e.g. `kv::MemStore::get_after_put`. Tests are named like
+/// those for generic functions, e.g. `kv::get_after_put::MemStore`.
+///
+/// Other type parameters can be instantiated through `types` as usual, and arguments can be fixed
+/// through `params`.
+///
+/// The following arguments are supported:
+///
+/// * `for`: the implementations to instantiate each function with. The value must be a string that
+///   parses as a comma-separated list of types, e.g. `for = "MemStore, DiskStore"`. Required.
+///
+/// ```
+/// use propfuzz::prelude::*;
+/// use std::collections::{BTreeMap, HashMap};
+///
+/// trait KvStore: Default {
+///     fn put(&mut self, key: u8, value: u32);
+///     fn get(&self, key: u8) -> Option<u32>;
+/// }
+///
+/// impl KvStore for BTreeMap<u8, u32> {
+///     // ...
+/// #   fn put(&mut self, key: u8, value: u32) {
+/// #       self.insert(key, value);
+/// #   }
+/// #
+/// #   fn get(&self, key: u8) -> Option<u32> {
+/// #       BTreeMap::get(self, &key).copied()
+/// #   }
+/// }
+///
+/// impl KvStore for HashMap<u8, u32> {
+///     // ...
+/// #   fn put(&mut self, key: u8, value: u32) {
+/// #       self.insert(key, value);
+/// #   }
+/// #
+/// #   fn get(&self, key: u8) -> Option<u32> {
+/// #       HashMap::get(self, &key).copied()
+/// #   }
+/// }
+///
+/// #[propfuzz_suite(for = "BTreeMap<u8, u32>, HashMap<u8, u32>")]
+/// mod kv {
+///     use super::*;
+///
+///     /// Values can be read back after they're put.
+///     #[propfuzz]
+///     fn get_after_put<S: KvStore>(key: u8, value: u32) {
+///         let mut store = S::default();
+///         store.put(key, value);
+///         prop_assert_eq!(store.get(key), Some(value));
+///     }
+/// }
+/// #
+/// # fn main() {}
+/// ```
+#[proc_macro_attribute]
+pub fn propfuzz_suite(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let attr = parse_macro_input!(attr as AttributeArgs);
+    let item = parse_macro_input!(item as Item);
+
+    suite_impl::propfuzz_suite_impl(attr, item)
+        .unwrap_or_else(|err| err)
+        .into()
+}
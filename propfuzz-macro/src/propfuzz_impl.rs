@@ -2,14 +2,16 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
 use crate::config::{
-    ConfigBuilder, ParamConfig, ParamConfigBuilder, PropfuzzConfig, PropfuzzConfigBuilder,
+    AsyncRuntime, ConfigBuilder, ParamConfig, ParamConfigBuilder, PropfuzzConfig,
+    PropfuzzConfigBuilder, TypeParamValues,
 };
 use crate::errors::*;
-use proc_macro2::{Ident, TokenStream};
+use proc_macro2::{Ident, Span, TokenStream};
 use quote::{format_ident, quote, ToTokens};
 use syn::{
-    Attribute, AttributeArgs, Block, FnArg, Index, ItemFn, Lit, Meta, NestedMeta, Pat, PatType,
-    Signature, Type,
+    parse_quote, Attribute, AttributeArgs, Block, Expr, FnArg, GenericParam, Generics,
+    ImplItemMethod, Index, ItemFn, Lit, Meta, NestedMeta, Pat, PatType, Receiver, ReturnType,
+    Signature, Type, Visibility,
 };
 
 pub(crate) fn propfuzz_impl(attr: AttributeArgs, item: ItemFn) -> Result<TokenStream, TokenStream> {
@@ -23,12 +25,21 @@ pub(crate) fn propfuzz_impl(attr: AttributeArgs, item: ItemFn) -> Result<TokenSt
 
 /// Processor for a propfuzz function.
 #[derive(Debug)]
-struct PropfuzzFn<'a> {
+pub(crate) struct PropfuzzFn<'a> {
     name: &'a Ident,
+    // The name of the test function, e.g. "reverse" or "Fixture__method" for methods in suites.
+    test_name: Ident,
+    // The path to the target within its module, e.g. "reverse" or "Fixture::method".
+    path: String,
+    // The suite this is part of, if any.
+    suite: Option<Suite<'a>>,
     description: Option<String>,
     other_attrs: Vec<&'a Attribute>,
     config: PropfuzzConfig,
     struct_name: Ident,
+    generics: &'a Generics,
+    // One instance per combination of types for generic functions, empty otherwise.
+    instances: Vec<Instance>,
     body: PropfuzzFnBody<'a>,
 }
 
@@ -37,18 +48,37 @@ impl<'a> PropfuzzFn<'a> {
 
     /// Creates a new instance of `PropfuzzFn`.
     fn new(attr: &'a [NestedMeta], item: &'a ItemFn) -> Result<Self> {
+        Self::from_parts(attr, &item.attrs, &item.sig, &item.block, None)
+    }
+
+    /// Creates a new instance of `PropfuzzFn` for a method in a `#[propfuzz_suite]` impl.
+    pub(crate) fn new_method(method: &'a ImplItemMethod, suite: Suite<'a>) -> Result<Self> {
+        Self::from_parts(&[], &method.attrs, &method.sig, &method.block, Some(suite))
+    }
+
+    /// Creates a new instance of `PropfuzzFn` for a function in a `#[propfuzz_suite]` module.
+    pub(crate) fn new_in_module(item: &'a ItemFn, suite: Suite<'a>) -> Result<Self> {
+        Self::from_parts(&[], &item.attrs, &item.sig, &item.block, Some(suite))
+    }
+
+    fn from_parts(
+        attr: &'a [NestedMeta],
+        attrs: &'a [Attribute],
+        sig: &'a Signature,
+        block: &'a Block,
+        suite: Option<Suite<'a>>,
+    ) -> Result<Self> {
         let mut errors = ErrorList::new();
         let mut config_builder = PropfuzzConfigBuilder::default();
 
         // Apply the arguments from the first #[propfuzz] invocation.
         config_builder.apply_args(attr, &mut errors);
 
-        let name = &item.sig.ident;
+        let name = &sig.ident;
 
         // Read the description from the doc comment.
         let description = {
-            let description = item
-                .attrs
+            let description = attrs
                 .iter()
                 .filter_map(|attr| {
                     if attr.path.is_ident("doc") {
@@ -66,44 +96,226 @@ impl<'a> PropfuzzFn<'a> {
         };
 
         // Read arguments from remaining #[propfuzz] attributes.
-        let (propfuzz_attrs, other_attrs) = item
-            .attrs
+        let (propfuzz_attrs, other_attrs) = attrs
             .iter()
             .partition::<Vec<_>, _>(|attr| attr.path.is_ident("propfuzz"));
 
+        // Methods in suites aren't invoked as macros, so they may have a bare #[propfuzz].
+        let propfuzz_attrs = propfuzz_attrs
+            .into_iter()
+            .filter(|attr| suite.is_none() || !attr.tokens.is_empty());
         config_builder.apply_attrs(propfuzz_attrs, &mut errors);
 
-        let body = match PropfuzzFnBody::new(&item.sig, &item.block) {
+        let mut config = config_builder.finish();
+        if let (Some((_, span)), None) = (&config.runtime, &sig.asyncness) {
+            errors.combine(Error::new(*span, "runtime requires an async fn"));
+        }
+        if let Some(Suite::Module { impls }) = suite {
+            if let Err(error) = add_impl_types(sig, impls, &mut config) {
+                return Err(errors.combine_finish(error));
+            }
+        }
+
+        let in_impl = matches!(suite, Some(Suite::Impl { .. }));
+        let body = match PropfuzzFnBody::new(sig, block, &config, in_impl) {
             Ok(body) => body,
             Err(error) => return Err(errors.combine_finish(error)),
         };
 
-        let struct_name = format_ident!("{}{}", Self::STRUCT_PREFIX, name);
+        // Methods are named after their type as well, e.g. `Fixture::method`.
+        let (test_name, path) = match suite {
+            Some(Suite::Impl { self_ty, .. }) => {
+                let ty_name = display_name(self_ty);
+                (
+                    format_ident!("{}__{}", ident_suffix(&ty_name), name),
+                    format!("{}::{}", ty_name, name),
+                )
+            }
+            Some(Suite::Module { .. }) | None => (name.clone(), name.to_string()),
+        };
+        let struct_name = format_ident!("{}{}", Self::STRUCT_PREFIX, test_name);
+
+        let instances = errors
+            .combine_opt(|| {
+                Instance::all(
+                    &sig.generics,
+                    &config,
+                    &body,
+                    &struct_name,
+                    suite.is_some() && !in_impl,
+                )
+            })
+            .unwrap_or_default();
+        if !instances.is_empty() && body.unstructured_param().is_some() {
+            errors.combine(Error::new_spanned(
+                &sig.generics,
+                "#[propfuzz] doesn't support generic functions with &[u8] or &str arguments",
+            ));
+        }
+
+        if let Some(corpus_generator) = &config.corpus_generator {
+            if body.unstructured_param().is_none() {
+                errors.combine(Error::new_spanned(
+                    corpus_generator,
+                    "corpus_generator requires a single &[u8] or &str argument",
+                ));
+            }
+        }
 
         // If any errors were collected, return them.
         errors.finish()?;
 
         Ok(Self {
-            name: &item.sig.ident,
+            name,
+            test_name,
+            path,
+            suite,
             description,
             other_attrs,
-            config: config_builder.finish(),
+            config,
             struct_name,
+            generics: &sig.generics,
+            instances,
             body,
         })
     }
+
+    /// Returns the method to put in place of this one in its `#[propfuzz_suite]` impl, along with
+    /// the constructor for its fixture if it takes `self`.
+    ///
+    /// The method runs the body as a test case, so it returns a `TestCaseResult`.
+    pub(crate) fn method_tokens(&self, vis: &Visibility) -> TokenStream {
+        let name = self.name;
+        let body = &self.body;
+        let docs = self
+            .other_attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("doc"));
+        let (impl_generics, _, where_clause) = self.generics.split_for_impl();
+        let receiver = self.body.receiver.map(|receiver| quote! { #receiver, });
+        let params = self.body.params.iter().map(|param| {
+            let name_pat = param.name_pat;
+            let ty = param.ty;
+            quote! { #name_pat: #ty }
+        });
+
+        let fixture_fn = match (self.suite, self.body.receiver) {
+            (Some(Suite::Impl { fixture, .. }), Some(_)) => {
+                let fixture_name = fixture_fn_name(name);
+                let fixture = match fixture {
+                    Some(fixture) => quote! { #fixture },
+                    None => quote! { <Self as ::std::default::Default>::default() },
+                };
+                quote! {
+                    #[doc(hidden)]
+                    fn #fixture_name() -> Self {
+                        #fixture
+                    }
+                }
+            }
+            _ => quote! {},
+        };
+
+        quote! {
+            #(#docs)*
+            #vis fn #name #impl_generics (#receiver #(#params),*) -> ::propfuzz::proptest::test_runner::TestCaseResult #where_clause {
+                #body
+            }
+
+            #fixture_fn
+        }
+    }
+
+    /// Returns the body of `StructuredTarget::test` for a method, which calls it with `args` on a
+    /// new fixture.
+    fn method_call(&self, self_ty: &Type, args: &[TokenStream]) -> TokenStream {
+        let name = self.name;
+        let params: Vec<_> = self
+            .generics
+            .type_params()
+            .map(|param| &param.ident)
+            .collect();
+        let turbofish = if params.is_empty() {
+            quote! {}
+        } else {
+            quote! { ::<#(#params),*> }
+        };
+
+        match self.body.receiver {
+            Some(_) => {
+                let fixture_name = fixture_fn_name(name);
+                quote! {
+                    #[allow(unused_mut)]
+                    let mut __propfuzz_fixture = <#self_ty>::#fixture_name();
+                    __propfuzz_fixture.#name #turbofish(#(#args),*)
+                }
+            }
+            None => quote! { <#self_ty>::#name #turbofish(#(#args),*) },
+        }
+    }
+}
+
+/// The `#[propfuzz_suite]` that a function is part of.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Suite<'a> {
+    /// An impl, whose methods are called on a fixture.
+    Impl {
+        self_ty: &'a Type,
+        // The expression that fixtures are built with, if not `Default::default()`.
+        fixture: Option<&'a Expr>,
+    },
+    /// A module, whose functions are instantiated with each implementation through their first
+    /// type parameter.
+    Module { impls: &'a [Type] },
+}
+
+/// Instantiates the first type parameter of a function in a `#[propfuzz_suite]` module with each
+/// implementation.
+fn add_impl_types(sig: &Signature, impls: &[Type], config: &mut PropfuzzConfig) -> Result<()> {
+    let param = match sig.generics.type_params().next() {
+        Some(param) => &param.ident,
+        None => {
+            return Err(Error::new_spanned(
+                &sig.ident,
+                "#[propfuzz] functions in #[propfuzz_suite] modules must have a type parameter for the implementation",
+            ))
+        }
+    };
+    if let Some(values) = config.types.iter().find(|values| values.param == *param) {
+        return Err(Error::new_spanned(
+            &values.param,
+            format!(
+                "type parameter {} is instantiated with the implementations from #[propfuzz_suite]",
+                param
+            ),
+        ));
+    }
+    config.types.push(TypeParamValues {
+        param: param.clone(),
+        types: impls.to_vec(),
+    });
+    Ok(())
+}
+
+/// Returns the name of the associated function that builds the fixture for a method.
+fn fixture_fn_name(name: &Ident) -> Ident {
+    format_ident!("__propfuzz_fixture_{}", name)
 }
 
 impl<'a> ToTokens for PropfuzzFn<'a> {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let Self {
-            name,
+            name: _,
+            test_name,
+            path,
+            suite,
             description,
             other_attrs,
             config,
             struct_name,
+            generics,
+            instances,
             body,
-            ..
         } = self;
 
         // The ToTokens impl for Option isn't quite what we want, so do this by hand.
@@ -112,52 +324,539 @@ impl<'a> ToTokens for PropfuzzFn<'a> {
             None => quote! { None },
         };
 
+        let fuzz_default = config.fuzz_default;
         let proptest_config = &config.proptest;
-        let types = body.types();
-        let name_pats = body.name_pats();
+        let examples = &config.examples;
+
+        let value_impl = match body.unstructured_param() {
+            Some((param, kind)) => {
+                let value_ty = &param.value_ty;
+                let strategy = param.config.strategy();
+                let name_pat = param.name_pat;
+                let ty = param.ty;
+                let value_from_bytes = kind.value_from_bytes_expr();
+                let value_to_bytes = kind.value_to_bytes_expr();
+                let corpus_generator = match &config.corpus_generator {
+                    Some(corpus_generator) => quote! { Some(#corpus_generator) },
+                    None => kind.default_corpus_generator(struct_name),
+                };
+                let test_body = match suite {
+                    Some(Suite::Impl { self_ty, .. }) => {
+                        self.method_call(self_ty, &[quote! { &__propfuzz_value }])
+                    }
+                    Some(Suite::Module { .. }) | None => quote! {
+                        let #name_pat: #ty = &__propfuzz_value;
+                        #body
+                    },
+                };
 
-        // Use indexes as tuple accessors in fmt_value.
-        // Note that we can't destructure values because name_pats may contain modifiers like mut.
-        // TODO: modifiers like mut can be filtered out -- consider doing so for a nicer display.
-        let indexes = (0..body.num_params()).map(Index::from);
+                quote! {
+                    type Value = #value_ty;
 
-        tokens.extend(quote! {
-            #[test]
-            #(#other_attrs )*
-            fn #name() {
-                ::propfuzz::runtime::execute_as_proptest(#struct_name);
+                    fn strategy(&self) -> ::propfuzz::proptest::strategy::BoxedStrategy<Self::Value> {
+                        ::propfuzz::proptest::strategy::Strategy::boxed(#strategy)
+                    }
+
+                    fn test(&self, __propfuzz_value: Self::Value) -> ::propfuzz::proptest::test_runner::TestCaseResult {
+                        #test_body
+                    }
+
+                    fn is_unstructured(&self) -> bool {
+                        true
+                    }
+
+                    fn value_from_bytes(&self, data: &[u8]) -> Option<Self::Value> {
+                        #value_from_bytes
+                    }
+
+                    fn value_to_bytes(&self, value: &Self::Value) -> Option<::std::vec::Vec<u8>> {
+                        #value_to_bytes
+                    }
+
+                    fn corpus_generator(&self) -> Option<fn(&mut ::propfuzz::corpus::ValueGenerator) -> ::std::vec::Vec<u8>> {
+                        #corpus_generator
+                    }
+
+                    fn fmt_value(&self, value: &Self::Value, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                        writeln!(f, "{} = {:?}", stringify!(#name_pat), value)
+                    }
+                }
             }
+            None => {
+                let types = body.types();
+                let strategies = body.strategies();
+                let name_pats: Vec<_> = body.name_pats().collect();
 
-            #[derive(Copy, Clone, Debug)]
-            #[allow(non_camel_case_types)]
-            struct #struct_name;
+                // Use indexes as tuple accessors in fmt_value.
+                // Note that we can't destructure values because name_pats may contain modifiers
+                // like mut.
+                // TODO: modifiers like mut can be filtered out -- consider doing so for a nicer
+                // display.
+                let indexes = (0..body.num_params()).map(Index::from);
+
+                // Unit doesn't implement Strategy, which matters if every argument is fixed.
+                let strategy = if body.num_params() == 0 {
+                    quote! { ::propfuzz::proptest::strategy::Just(()) }
+                } else {
+                    quote! { (#(#strategies,)*) }
+                };
+                // Methods are called with generated values bound to placeholder names instead.
+                let (test_pats, test_body) = match suite {
+                    Some(Suite::Impl { self_ty, .. }) => (
+                        body.arg_names()
+                            .map(|name| quote! { #name })
+                            .collect::<Vec<_>>(),
+                        self.method_call(self_ty, &body.call_args()),
+                    ),
+                    Some(Suite::Module { .. }) | None => {
+                        let fixed_bindings = body.fixed_bindings();
+                        (
+                            name_pats
+                                .iter()
+                                .map(|name_pat| quote! { #name_pat })
+                                .collect(),
+                            quote! {
+                                #(#fixed_bindings)*
+                                #body
+                            },
+                        )
+                    }
+                };
+
+                quote! {
+                    type Value = (#(#types,)*);
+
+                    fn strategy(&self) -> ::propfuzz::proptest::strategy::BoxedStrategy<Self::Value> {
+                        ::propfuzz::proptest::strategy::Strategy::boxed(#strategy)
+                    }
+
+                    fn test(&self, (#(#test_pats,)*): Self::Value) -> ::propfuzz::proptest::test_runner::TestCaseResult {
+                        #test_body
+                    }
+
+                    fn fmt_value(&self, value: &Self::Value, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                        #(writeln!(f, "{} = {:?}", stringify!(#name_pats), value.#indexes)?;)*
+                        Ok(())
+                    }
+                }
+            }
+        };
 
-            impl ::propfuzz::traits::StructuredTarget for #struct_name {
-                type Value = (#(#types,)*);
+        // Boxed strategies require values to be 'static.
+        let mut generics = (*generics).clone();
+        let params: Vec<_> = generics
+            .type_params()
+            .map(|param| param.ident.clone())
+            .collect();
+        for param in &params {
+            generics
+                .make_where_clause()
+                .predicates
+                .push(parse_quote! { #param: 'static });
+        }
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        let (target_name, registration) = if instances.is_empty() {
+            (
+                quote! { concat!(module_path!(), "::", #path) },
+                // Register this target so that it can be found through the registry. This is done
+                // within the impl so that if #[propfuzz] is used within an impl block, which can't
+                // be detected for functions without a receiver, the only errors are about the
+                // struct and impl not being supported there.
+                quote! {
+                    #[::propfuzz::__private::linkme::distributed_slice(::propfuzz::registry::__PROPFUZZ_TARGETS)]
+                    #[linkme(crate = ::propfuzz::__private::linkme)]
+                    static REGISTRATION: &'static dyn ::propfuzz::registry::RegisteredTarget = &#struct_name;
+                },
+            )
+        } else {
+            (quote! { self.name }, quote! {})
+        };
 
+        let target_impl = quote! {
+            impl #impl_generics ::propfuzz::traits::StructuredTarget for #struct_name #ty_generics #where_clause {
                 fn name(&self) -> &'static str {
-                    concat!(module_path!(), "::", stringify!(#name))
+                    #registration
+
+                    #target_name
                 }
 
                 fn description(&self) -> Option<&'static str> {
                     #description
                 }
 
+                fn fuzz_default(&self) -> bool {
+                    #fuzz_default
+                }
+
                 fn proptest_config(&self) -> ::propfuzz::proptest::test_runner::Config {
                     #proptest_config
                 }
 
-                fn execute(&self, __propfuzz_test_runner: &mut ::propfuzz::proptest::test_runner::TestRunner)
-                    -> ::std::result::Result<(), ::propfuzz::proptest::test_runner::TestError<Self::Value>> {
-                    #body
+                fn examples(&self) -> ::std::vec::Vec<Self::Value> {
+                    ::std::vec![#(#examples,)*]
                 }
 
-                fn fmt_value(&self, value: &Self::Value, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
-                    #(writeln!(f, "{} = {:?}", stringify!(#name_pats), value.#indexes)?;)*
-                    Ok(())
+                #value_impl
+            }
+        };
+
+        // Tests for methods are named after their type as well, e.g. `Fixture__method`.
+        let allow_non_snake_case = if matches!(suite, Some(Suite::Impl { .. })) {
+            quote! { #[allow(non_snake_case)] }
+        } else {
+            quote! {}
+        };
+
+        if instances.is_empty() {
+            // #[test] functions only exist in test builds anyway. Saying so up front means that if
+            // this is used within an impl block, builds outside tests only report the struct and
+            // impl errors.
+            tokens.extend(quote! {
+                #[cfg(test)]
+                #[test]
+                #(#other_attrs )*
+                #allow_non_snake_case
+                fn #test_name() {
+                    ::propfuzz::runtime::execute_as_proptest(#struct_name);
                 }
+
+                #[derive(Copy, Clone, Debug)]
+                #[allow(non_camel_case_types)]
+                struct #struct_name;
+
+                #target_impl
+            });
+            return;
+        }
+
+        // Generic and parameterized functions get a single target type, with a constant and a
+        // test for each instance. Fixed arguments are looked up by row.
+        let num_fixed = body.fixed_params().count();
+        let params_field = if num_fixed > 0 {
+            quote! { params: [usize; #num_fixed], }
+        } else {
+            quote! {}
+        };
+        let instance_consts = instances.iter().map(|instance| {
+            let Instance {
+                types,
+                rows,
+                path_prefix,
+                name_suffix,
+                const_name,
+                ..
+            } = instance;
+            let params_field = if num_fixed > 0 {
+                quote! { params: [#(#rows),*], }
+            } else {
+                quote! {}
+            };
+            quote! {
+                #[allow(non_upper_case_globals)]
+                const #const_name: #struct_name<#(#types),*> = #struct_name {
+                    name: concat!(module_path!(), "::", #path_prefix, #path, #name_suffix),
+                    #params_field
+                    _marker: ::std::marker::PhantomData,
+                };
             }
         });
+        let instance_tests = instances.iter().map(|instance| {
+            let Instance {
+                test_name,
+                const_name,
+                ..
+            } = instance;
+            quote! {
+                #[test]
+                #(#other_attrs )*
+                #[allow(non_snake_case)]
+                fn #test_name() {
+                    ::propfuzz::runtime::execute_as_proptest(super::#const_name);
+                }
+            }
+        });
+        let registered_names = instances.iter().map(|instance| &instance.const_name);
+
+        tokens.extend(quote! {
+            #[allow(non_camel_case_types)]
+            struct #struct_name<#(#params),*> {
+                name: &'static str,
+                #params_field
+                _marker: ::std::marker::PhantomData<fn() -> (#(#params,)*)>,
+            }
+
+            impl<#(#params),*> ::std::clone::Clone for #struct_name<#(#params),*> {
+                fn clone(&self) -> Self {
+                    *self
+                }
+            }
+
+            impl<#(#params),*> ::std::marker::Copy for #struct_name<#(#params),*> {}
+
+            impl<#(#params),*> ::std::fmt::Debug for #struct_name<#(#params),*> {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                    f.write_str(self.name)
+                }
+            }
+
+            #target_impl
+
+            #(#instance_consts)*
+
+            // The tests for each instance are named after their types and rows, e.g. `name::u8`.
+            #allow_non_snake_case
+            mod #test_name {
+                #[allow(unused_imports)]
+                use super::*;
+
+                #(#instance_tests)*
+            }
+
+            // Register each instance so that it can be found through the registry.
+            #(
+                const _: () = {
+                    #[::propfuzz::__private::linkme::distributed_slice(::propfuzz::registry::__PROPFUZZ_TARGETS)]
+                    #[linkme(crate = ::propfuzz::__private::linkme)]
+                    static REGISTRATION: &'static dyn ::propfuzz::registry::RegisteredTarget = &#registered_names;
+                };
+            )*
+        });
+    }
+}
+
+/// A single instance of a generic or parameterized propfuzz function.
+#[derive(Debug)]
+struct Instance {
+    // The types for each type parameter, in the order they're declared in.
+    types: Vec<Type>,
+    // The row for each fixed argument, in the order they're declared in.
+    rows: Vec<usize>,
+    // The prefix added to the function name in the target name, e.g. "MemStore::" for the
+    // implementation in suite modules.
+    path_prefix: String,
+    // The suffix added to the function name in the target name, e.g. "::<u8>[mode=Fast]".
+    name_suffix: String,
+    // The name of the test function, e.g. "u8_mode_Fast".
+    test_name: Ident,
+    // The name of the constant for the target.
+    const_name: Ident,
+}
+
+impl Instance {
+    /// Returns every combination of the types specified through `types(...)` and the rows
+    /// specified through `params(...)`, or an empty list for other functions.
+    ///
+    /// In suite modules, the first type parameter is the implementation, which is part of the
+    /// target's path rather than its suffix.
+    fn all(
+        generics: &Generics,
+        config: &PropfuzzConfig,
+        body: &PropfuzzFnBody,
+        struct_name: &Ident,
+        in_module: bool,
+    ) -> Result<Vec<Self>> {
+        let mut errors = ErrorList::new();
+
+        for param in &generics.params {
+            if !matches!(param, GenericParam::Type(_)) {
+                errors.combine(Error::new_spanned(
+                    param,
+                    "#[propfuzz] only supports generic type parameters",
+                ));
+            }
+        }
+        for values in &config.types {
+            if !generics
+                .type_params()
+                .any(|param| param.ident == values.param)
+            {
+                errors.combine(Error::new_spanned(
+                    &values.param,
+                    "not a type parameter of this function",
+                ));
+            }
+        }
+
+        // Build up the cartesian product of types for each parameter, in declaration order.
+        let mut type_combinations: Vec<Vec<&Type>> = vec![vec![]];
+        for param in generics.type_params() {
+            let values = match config
+                .types
+                .iter()
+                .find(|values| values.param == param.ident)
+            {
+                Some(values) => values,
+                None => {
+                    errors.combine(Error::new_spanned(
+                        param,
+                        format!(
+                            "type parameter {} must be instantiated through types({} = \"...\")",
+                            param.ident, param.ident
+                        ),
+                    ));
+                    continue;
+                }
+            };
+            let types: Vec<_> = values.types.iter().collect();
+            type_combinations = product(type_combinations, &types);
+        }
+
+        errors.finish()?;
+        if generics.params.is_empty() && body.fixed_params().next().is_none() {
+            return Ok(vec![]);
+        }
+
+        // Then the cartesian product of rows for each fixed argument.
+        let mut row_combinations: Vec<Vec<usize>> = vec![vec![]];
+        for (_, values) in body.fixed_params() {
+            let rows: Vec<_> = (0..values.len()).collect();
+            row_combinations = product(row_combinations, &rows);
+        }
+
+        let mut instances = vec![];
+        for types in &type_combinations {
+            for rows in &row_combinations {
+                let type_names: Vec<_> = types.iter().map(display_name).collect();
+                let (path_prefix, suffix_types) = if in_module {
+                    (format!("{}::", type_names[0]), &type_names[1..])
+                } else {
+                    (String::new(), &type_names[..])
+                };
+                let row_names: Vec<_> = body
+                    .fixed_params()
+                    .zip(rows)
+                    .map(|((param, values), row)| {
+                        let name_pat = param.name_pat;
+                        (quote!(#name_pat).to_string(), value_label(&values[*row]))
+                    })
+                    .collect();
+
+                let mut name_suffix = String::new();
+                if !suffix_types.is_empty() {
+                    name_suffix.push_str(&format!("::<{}>", suffix_types.join(", ")));
+                }
+                if !row_names.is_empty() {
+                    let row_names: Vec<_> = row_names
+                        .iter()
+                        .map(|(param, label)| format!("{}={}", param, label))
+                        .collect();
+                    name_suffix.push_str(&format!("[{}]", row_names.join(", ")));
+                }
+
+                let test_name = type_names
+                    .iter()
+                    .map(|name| ident_suffix(name))
+                    .chain(
+                        row_names
+                            .iter()
+                            .map(|(param, label)| ident_suffix(&format!("{} {}", param, label))),
+                    )
+                    .collect::<Vec<_>>()
+                    .join("_");
+
+                instances.push(Self {
+                    types: types.iter().map(|ty| (*ty).clone()).collect(),
+                    rows: rows.clone(),
+                    path_prefix,
+                    name_suffix,
+                    test_name: format_ident!("{}", test_name),
+                    const_name: format_ident!("{}__{}", struct_name, test_name),
+                });
+            }
+        }
+
+        let mut errors = ErrorList::new();
+        for (i, instance) in instances.iter().enumerate() {
+            if let Some(other) = instances[..i]
+                .iter()
+                .find(|other| other.test_name == instance.test_name)
+            {
+                errors.combine(Error::new(
+                    Span::call_site(),
+                    format!(
+                        "{}{} and {}{} would both be tested as {}",
+                        other.path_prefix,
+                        other.name_suffix,
+                        instance.path_prefix,
+                        instance.name_suffix,
+                        instance.test_name
+                    ),
+                ));
+            }
+        }
+        errors.finish()?;
+
+        Ok(instances)
+    }
+}
+
+/// Extends each combination with each of the given values.
+fn product<T: Clone>(combinations: Vec<Vec<T>>, values: &[T]) -> Vec<Vec<T>> {
+    combinations
+        .into_iter()
+        .flat_map(|prefix| {
+            values.iter().map(move |value| {
+                let mut combination = prefix.clone();
+                combination.push(value.clone());
+                combination
+            })
+        })
+        .collect()
+}
+
+/// Returns the label for a fixed value in target names: the last segment for paths such as
+/// `Mode::Fast`, and the expression itself otherwise.
+fn value_label(value: &Expr) -> String {
+    match value {
+        Expr::Path(path) if path.qself.is_none() => match path.path.segments.last() {
+            Some(segment) => segment.ident.to_string(),
+            None => display_name(value),
+        },
+        _ => display_name(value),
+    }
+}
+
+/// Formats a type or expression for display, removing the spaces that quote adds between tokens.
+fn display_name(tokens: &impl ToTokens) -> String {
+    let tokens = tokens.to_token_stream().to_string();
+    let chars: Vec<char> = tokens.chars().collect();
+    let is_ident_char = |c: Option<&char>| matches!(c, Some(c) if c.is_alphanumeric() || *c == '_');
+
+    let mut name = String::with_capacity(tokens.len());
+    for (i, &c) in chars.iter().enumerate() {
+        match c {
+            // Keep spaces between identifiers, e.g. in `dyn Trait`.
+            ' ' if is_ident_char(name.chars().last().as_ref())
+                && is_ident_char(chars.get(i + 1)) =>
+            {
+                name.push(' ')
+            }
+            ' ' => (),
+            ',' => name.push_str(", "),
+            c => name.push(c),
+        }
+    }
+    name.replace(",  ", ", ")
+}
+
+/// Turns a display name into something that can be used within an identifier.
+fn ident_suffix(name: &str) -> String {
+    let mut suffix = String::with_capacity(name.len());
+    for c in name.chars() {
+        if c.is_alphanumeric() {
+            suffix.push(c);
+        } else if !suffix.is_empty() && !suffix.ends_with('_') {
+            suffix.push('_');
+        }
+    }
+    let suffix = suffix.trim_end_matches('_');
+    if suffix.is_empty() {
+        // For example, the unit type.
+        "unit".to_string()
+    } else if suffix.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", suffix)
+    } else {
+        suffix.to_string()
     }
 }
 
@@ -174,13 +873,28 @@ fn extract_doc_comment(attr: &Attribute) -> Result<String> {
 /// The body of a proptest function.
 #[derive(Debug)]
 struct PropfuzzFnBody<'a> {
+    // The receiver, for methods in suites.
+    receiver: Option<&'a Receiver>,
     params: Vec<PropfuzzParam<'a>>,
+    // The return type, if the function returns a Result.
+    result_ty: Option<&'a Type>,
+    // The executor to block on the body with, if the function is async.
+    runtime: Option<AsyncRuntime>,
     block: &'a Block,
 }
 
 impl<'a> PropfuzzFnBody<'a> {
-    fn new(sig: &'a Signature, block: &'a Block) -> Result<Self> {
-        if sig.inputs.is_empty() {
+    fn new(
+        sig: &'a Signature,
+        block: &'a Block,
+        config: &PropfuzzConfig,
+        in_suite: bool,
+    ) -> Result<Self> {
+        if !sig
+            .inputs
+            .iter()
+            .any(|param| matches!(param, FnArg::Typed(_)))
+        {
             return Err(Error::new_spanned(
                 sig,
                 "#[propfuzz] requires at least one argument",
@@ -189,83 +903,285 @@ impl<'a> PropfuzzFnBody<'a> {
 
         let mut errors = ErrorList::new();
 
+        let mut receiver = None;
         let params = sig
             .inputs
             .iter()
             .filter_map(|param| match param {
-                FnArg::Receiver(receiver) => {
+                FnArg::Receiver(param) if in_suite => {
+                    receiver = Some(param);
+                    None
+                }
+                FnArg::Receiver(param) => {
                     errors.combine(Error::new_spanned(
-                        receiver,
-                        "#[propfuzz] is only supported on top-level functions",
+                        param,
+                        "#[propfuzz] is only supported on top-level functions and in #[propfuzz_suite] impls",
                     ));
                     None
                 }
-                FnArg::Typed(param) => errors.combine_opt(|| PropfuzzParam::new(param)),
+                FnArg::Typed(param) => errors.combine_opt(|| PropfuzzParam::new(param, config)),
             })
             .collect::<Vec<_>>();
 
+        for values in &config.params {
+            let is_argument = sig.inputs.iter().any(|param| match param {
+                FnArg::Typed(param) => {
+                    matches!(&*param.pat, Pat::Ident(pat) if pat.ident == values.param)
+                }
+                FnArg::Receiver(_) => false,
+            });
+            if !is_argument {
+                errors.combine(Error::new_spanned(
+                    &values.param,
+                    "not an argument of this function",
+                ));
+            }
+        }
+
+        // Unstructured parameters take the entire input, so they can't be combined with others.
+        if params.iter().filter(|param| param.fixed.is_none()).count() > 1 {
+            for param in &params {
+                if param.unstructured.is_some() {
+                    errors.combine(Error::new_spanned(
+                        param.ty,
+                        "#[propfuzz] requires &[u8] or &str arguments to be the only argument",
+                    ));
+                }
+            }
+        }
+
+        let result_ty = match &sig.output {
+            ReturnType::Default => None,
+            ReturnType::Type(_, ty) => {
+                if !is_result_type(ty) {
+                    errors.combine(Error::new_spanned(
+                        ty,
+                        "#[propfuzz] functions must return () or Result<(), E>",
+                    ));
+                }
+                Some(&**ty)
+            }
+        };
+
         // If there are any errors, return them.
         errors.finish()?;
 
-        Ok(Self { params, block })
+        let runtime = sig.asyncness.map(|_| match config.runtime {
+            Some((runtime, _)) => runtime,
+            None => AsyncRuntime::Builtin,
+        });
+
+        Ok(Self {
+            receiver,
+            params,
+            result_ty,
+            runtime,
+            block,
+        })
     }
 
     fn num_params(&self) -> usize {
-        self.params.len()
+        self.generated_params().count()
+    }
+
+    /// Returns the parameter and its kind if this function takes a single unstructured input.
+    fn unstructured_param(&self) -> Option<(&PropfuzzParam<'a>, UnstructuredKind)> {
+        match self.params.as_slice() {
+            [param] => param.unstructured.map(|kind| (param, kind)),
+            _ => None,
+        }
+    }
+
+    /// Returns the parameters that values are generated for.
+    fn generated_params(&self) -> impl Iterator<Item = &PropfuzzParam<'a>> + '_ {
+        self.params.iter().filter(|param| param.fixed.is_none())
+    }
+
+    /// Returns the parameters set through `params(...)`, along with their values.
+    fn fixed_params(&self) -> impl Iterator<Item = (&PropfuzzParam<'a>, &[Expr])> + '_ {
+        self.params.iter().filter_map(|param| {
+            param
+                .fixed
+                .as_ref()
+                .map(|values| (param, values.as_slice()))
+        })
     }
 
     fn types(&self) -> impl Iterator<Item = impl ToTokens + '_> + '_ {
-        self.params.iter().map(|param| param.ty)
+        self.generated_params().map(|param| &param.value_ty)
     }
 
     fn strategies(&self) -> impl Iterator<Item = impl ToTokens + '_> + '_ {
-        self.params.iter().map(|param| param.config.strategy())
+        self.generated_params().map(|param| param.config.strategy())
     }
 
     fn name_pats(&self) -> impl Iterator<Item = impl ToTokens + '_> + '_ {
-        self.params.iter().map(|param| param.name_pat)
+        self.generated_params().map(|param| param.name_pat)
+    }
+
+    /// Returns the placeholder names that generated values are bound to when calling methods.
+    fn arg_names(&self) -> impl Iterator<Item = Ident> {
+        (0..self.num_params()).map(|index| format_ident!("__propfuzz_arg{}", index))
+    }
+
+    /// Returns the arguments to call a method with, in declaration order: the placeholder names
+    /// for generated values and the values for this target's row for fixed ones.
+    fn call_args(&self) -> Vec<TokenStream> {
+        let mut arg_names = self.arg_names();
+        let mut fixed_index = 0;
+        self.params
+            .iter()
+            .map(|param| match &param.fixed {
+                Some(values) => {
+                    let value = fixed_value(fixed_index, param, values);
+                    fixed_index += 1;
+                    value
+                }
+                None => {
+                    let name = arg_names.next().expect("one name per generated parameter");
+                    quote! { #name }
+                }
+            })
+            .collect()
+    }
+
+    /// Returns statements that bind each fixed parameter to the value for this target's row.
+    fn fixed_bindings(&self) -> impl Iterator<Item = TokenStream> + '_ {
+        self.fixed_params()
+            .enumerate()
+            .map(|(index, (param, values))| {
+                let name_pat = param.name_pat;
+                let ty = param.ty;
+                let value = fixed_value(index, param, values);
+                quote! {
+                    let #name_pat: #ty = #value;
+                }
+            })
+    }
+}
+
+/// Returns an expression for the value of the fixed parameter at `index` for this target's row.
+fn fixed_value(index: usize, param: &PropfuzzParam, values: &[Expr]) -> TokenStream {
+    let name_pat = param.name_pat;
+    let rows = 0..values.len();
+    quote! {
+        match self.params[#index] {
+            #(#rows => #values,)*
+            _ => unreachable!("invalid row for {}", stringify!(#name_pat)),
+        }
     }
 }
 
 impl<'a> ToTokens for PropfuzzFnBody<'a> {
     fn to_tokens(&self, tokens: &mut TokenStream) {
-        let strategies = self.strategies();
-        let name_pats = self.name_pats();
         let block = self.block;
 
+        let result = match (self.runtime, self.result_ty) {
+            (None, None) => {
+                return tokens.extend(quote! {
+                    // This is similar to proptest -- it ensures that the block itself doesn't
+                    // return a value, other than through an explicit `return` statement (as with
+                    // the prop_assert_ methods).
+                    let _: () = #block;
+                    Ok(())
+                });
+            }
+            // Run the block in a closure so that `return` and `?` behave as they would in the
+            // original function.
+            (None, Some(result_ty)) => quote! { (|| -> #result_ty #block)() },
+            // For async functions, `return` returns from the async block instead, so the block
+            // has to return a TestCaseResult for the prop_assert_ methods to work.
+            (Some(runtime), None) => {
+                return tokens.extend(quote! {
+                    #runtime(::propfuzz::__private::with_output::<::propfuzz::proptest::test_runner::TestCaseResult, _>(
+                        async move {
+                            let _: () = #block;
+                            Ok(())
+                        }
+                    ))
+                });
+            }
+            (Some(runtime), Some(result_ty)) => quote! {
+                #runtime(::propfuzz::__private::with_output::<#result_ty, _>(async move #block))
+            },
+        };
+
         tokens.extend(quote! {
-            __propfuzz_test_runner.run(&(#(#strategies,)*), |(#(#name_pats,)*)| {
-                // This is similar to proptest -- it ensures that the block itself doesn't
-                // return a value, other than through an explicit `return` statement (as with
-                // the prop_assert_ methods).
-                let _: () = #block;
-                Ok(())
-            })
+            match #result {
+                Ok(()) => Ok(()),
+                Err(err) => {
+                    #[allow(unused_imports)]
+                    use ::propfuzz::__private::{ViaDisplay as _, ViaTestCaseError as _};
+                    Err((&::propfuzz::__private::ResultError(err)).to_test_case_error())
+                }
+            }
         });
     }
 }
 
+/// Returns true if this type looks like a `Result`, including aliases named `Result` such as
+/// `io::Result`.
+fn is_result_type(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => match path.path.segments.last() {
+            Some(segment) => segment.ident == "Result",
+            None => false,
+        },
+        _ => false,
+    }
+}
+
 /// A propfuzz input parameter representing a random instance of a specific type, and a strategy to
 /// generate that random instance.
 #[derive(Debug)]
 struct PropfuzzParam<'a> {
     name_pat: &'a Pat,
     ty: &'a Type,
+    // The type of values generated for this parameter. This is the same as ty, except for
+    // unstructured parameters.
+    value_ty: Type,
+    unstructured: Option<UnstructuredKind>,
     config: ParamConfig,
+    // The table of values for arguments fixed through `params(...)`. These aren't generated.
+    fixed: Option<Vec<Expr>>,
 }
 
 impl<'a> PropfuzzParam<'a> {
-    fn new(param: &'a PatType) -> Result<Self> {
+    fn new(param: &'a PatType, config: &PropfuzzConfig) -> Result<Self> {
         let ty = &*param.ty;
+        let fixed = match &*param.pat {
+            Pat::Ident(pat) => config
+                .params
+                .iter()
+                .find(|values| values.param == pat.ident)
+                .map(|values| values.values.clone()),
+            _ => None,
+        };
+        let unstructured = match fixed {
+            Some(_) => None,
+            None => UnstructuredKind::new(ty),
+        };
+        let value_ty = match unstructured {
+            Some(kind) => kind.value_ty(),
+            None => ty.clone(),
+        };
 
         let mut errors = ErrorList::new();
 
-        let mut config_builder = ParamConfigBuilder::new(ty);
+        let mut config_builder = ParamConfigBuilder::new(&value_ty);
         let (propfuzz_attrs, other_attrs) = param
             .attrs
             .iter()
             .partition::<Vec<_>, _>(|attr| attr.path.is_ident("propfuzz"));
 
+        if fixed.is_some() {
+            for attr in &propfuzz_attrs {
+                errors.combine(Error::new_spanned(
+                    attr,
+                    "#[propfuzz] attributes are not supported on arguments set through params",
+                ));
+            }
+        }
         config_builder.apply_attrs(propfuzz_attrs, &mut errors);
 
         // Non-propfuzz attributes on arguments aren't recognized (there's nowhere to put them!)
@@ -282,7 +1198,81 @@ impl<'a> PropfuzzParam<'a> {
         Ok(Self {
             name_pat: &param.pat,
             ty,
+            value_ty,
+            unstructured,
             config,
+            fixed,
         })
     }
 }
+
+/// The kind of an unstructured parameter, which receives fuzzer input directly.
+#[derive(Clone, Copy, Debug)]
+enum UnstructuredKind {
+    /// A `&[u8]` parameter.
+    Bytes,
+    /// A `&str` parameter.
+    Str,
+}
+
+impl UnstructuredKind {
+    /// Returns the kind of unstructured parameter this type represents, if any.
+    fn new(ty: &Type) -> Option<Self> {
+        let elem = match ty {
+            Type::Reference(reference) if reference.mutability.is_none() => &*reference.elem,
+            _ => return None,
+        };
+        match elem {
+            Type::Slice(slice) => match &*slice.elem {
+                Type::Path(path) if path.qself.is_none() && path.path.is_ident("u8") => {
+                    Some(UnstructuredKind::Bytes)
+                }
+                _ => None,
+            },
+            Type::Path(path) if path.qself.is_none() && path.path.is_ident("str") => {
+                Some(UnstructuredKind::Str)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the owned type that values are generated as.
+    fn value_ty(self) -> Type {
+        match self {
+            UnstructuredKind::Bytes => parse_quote! { ::std::vec::Vec<u8> },
+            UnstructuredKind::Str => parse_quote! { ::std::string::String },
+        }
+    }
+
+    /// Returns an expression that converts fuzzer input in `data` to an `Option<Self::Value>`.
+    fn value_from_bytes_expr(self) -> TokenStream {
+        match self {
+            UnstructuredKind::Bytes => quote! { Some(data.to_vec()) },
+            UnstructuredKind::Str => quote! {
+                ::std::str::from_utf8(data).ok().map(::std::string::ToString::to_string)
+            },
+        }
+    }
+
+    /// Returns an expression that converts `value` back into fuzzer input.
+    fn value_to_bytes_expr(self) -> TokenStream {
+        match self {
+            UnstructuredKind::Bytes => quote! { Some(value.clone()) },
+            UnstructuredKind::Str => quote! { Some(value.clone().into_bytes()) },
+        }
+    }
+
+    /// Returns an expression for a corpus generator that draws inputs from the target's strategy.
+    fn default_corpus_generator(self, struct_name: &Ident) -> TokenStream {
+        let into_bytes = match self {
+            UnstructuredKind::Bytes => quote! {},
+            UnstructuredKind::Str => quote! { .into_bytes() },
+        };
+        quote! {
+            fn generate(generator: &mut ::propfuzz::corpus::ValueGenerator) -> ::std::vec::Vec<u8> {
+                generator.new_value(::propfuzz::traits::StructuredTarget::strategy(&#struct_name))#into_bytes
+            }
+            Some(generate)
+        }
+    }
+}
@@ -0,0 +1,155 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::config::{ConfigBuilder, SuiteConfigBuilder};
+use crate::errors::*;
+use crate::propfuzz_impl::{PropfuzzFn, Suite};
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{Attribute, AttributeArgs, ImplItem, Item, ItemImpl, ItemMod, NestedMeta};
+
+pub(crate) fn propfuzz_suite_impl(
+    attr: AttributeArgs,
+    item: Item,
+) -> Result<TokenStream, TokenStream> {
+    let result = match &item {
+        Item::Impl(item) => expand_impl(&attr, item),
+        Item::Mod(item) => expand_mod(&attr, item),
+        _ => Err(Error::new_spanned(
+            &item,
+            "#[propfuzz_suite] is only supported on impl blocks and modules",
+        )),
+    };
+
+    result.map_err(|err| err.to_compile_error())
+}
+
+/// Expands a `#[propfuzz_suite]` impl, replacing each `#[propfuzz]` method with one that runs its
+/// body as a test case, and adding a target for each of them.
+fn expand_impl(attr: &[NestedMeta], item: &ItemImpl) -> Result<TokenStream> {
+    let mut errors = ErrorList::new();
+    let mut config_builder = SuiteConfigBuilder::default();
+    config_builder.apply_args(attr, &mut errors);
+    let config = config_builder.finish();
+
+    if let Some((_, span)) = &config.impls {
+        errors.combine(Error::new(*span, "for is only supported on modules"));
+    }
+    if let Some((_, path, _)) = &item.trait_ {
+        errors.combine(Error::new_spanned(
+            path,
+            "#[propfuzz_suite] is not supported on trait impls",
+        ));
+    }
+    if !item.generics.params.is_empty() {
+        errors.combine(Error::new_spanned(
+            &item.generics,
+            "#[propfuzz_suite] doesn't support generic impls",
+        ));
+    }
+
+    let suite = Suite::Impl {
+        self_ty: &item.self_ty,
+        fixture: config.fixture.as_ref(),
+    };
+
+    let mut output = item.clone();
+    output.items.clear();
+    let mut targets = vec![];
+    for impl_item in &item.items {
+        match impl_item {
+            ImplItem::Method(method) if has_propfuzz_attr(&method.attrs) => {
+                if let Some(propfuzz_fn) =
+                    errors.combine_opt(|| PropfuzzFn::new_method(method, suite))
+                {
+                    output
+                        .items
+                        .push(ImplItem::Verbatim(propfuzz_fn.method_tokens(&method.vis)));
+                    targets.push(propfuzz_fn.into_token_stream());
+                }
+            }
+            other => output.items.push(other.clone()),
+        }
+    }
+
+    // If any errors were collected, return them.
+    errors.finish()?;
+
+    Ok(quote! {
+        #output
+
+        #(#targets)*
+    })
+}
+
+/// Expands a `#[propfuzz_suite]` module, replacing each `#[propfuzz]` function with a target for
+/// each implementation.
+fn expand_mod(attr: &[NestedMeta], item: &ItemMod) -> Result<TokenStream> {
+    let mut errors = ErrorList::new();
+    let mut config_builder = SuiteConfigBuilder::default();
+    config_builder.apply_args(attr, &mut errors);
+    let config = config_builder.finish();
+
+    if let Some(fixture) = &config.fixture {
+        errors.combine(Error::new_spanned(
+            fixture,
+            "fixture is only supported on impl blocks",
+        ));
+    }
+    let impls = match &config.impls {
+        Some((impls, _)) => impls.as_slice(),
+        None => {
+            // If for was specified but failed to parse, an error was already reported.
+            let specified = attr
+                .iter()
+                .any(|arg| matches!(arg, NestedMeta::Meta(meta) if meta.path().is_ident("for")));
+            if !specified {
+                errors.combine(Error::new_spanned(
+                    &item.ident,
+                    "#[propfuzz_suite] on modules requires for = \"...\"",
+                ));
+            }
+            &[]
+        }
+    };
+    let content = match &item.content {
+        Some((_, content)) => content,
+        None => {
+            return Err(errors.combine_finish(Error::new_spanned(
+                item,
+                "#[propfuzz_suite] is only supported on inline modules",
+            )))
+        }
+    };
+    errors.finish()?;
+
+    let suite = Suite::Module { impls };
+
+    let mut errors = ErrorList::new();
+    let mut output = item.clone();
+    let mut items = vec![];
+    for inner_item in content {
+        match inner_item {
+            Item::Fn(inner_fn) if has_propfuzz_attr(&inner_fn.attrs) => {
+                if let Some(propfuzz_fn) =
+                    errors.combine_opt(|| PropfuzzFn::new_in_module(inner_fn, suite))
+                {
+                    items.push(Item::Verbatim(propfuzz_fn.into_token_stream()));
+                }
+            }
+            other => items.push(other.clone()),
+        }
+    }
+    if let Some((_, content)) = &mut output.content {
+        *content = items;
+    }
+
+    // If any errors were collected, return them.
+    errors.finish()?;
+
+    Ok(output.into_token_stream())
+}
+
+fn has_propfuzz_attr(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| attr.path.is_ident("propfuzz"))
+}
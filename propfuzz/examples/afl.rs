@@ -0,0 +1,40 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Runs a propfuzz target under AFL++.
+//!
+//! Build and run with:
+//!
+//! ```text
+//! cargo afl build --example afl --features afl
+//! cargo afl fuzz -i in -o out target/debug/examples/afl
+//! ```
+//!
+//! Without `cargo afl`, the example builds, but its entry point is left out since there's no
+//! AFL++ runtime to link against.
+
+use propfuzz::prelude::*;
+use proptest::collection::vec;
+
+/// Test that reversing a list twice produces the same results.
+#[propfuzz]
+fn reverse(#[propfuzz(strategy = "vec(any::<u32>(), 0..64)")] mut list: Vec<u32>) {
+    let list2 = list.clone();
+    list.reverse();
+    list.reverse();
+    prop_assert_eq!(list, list2);
+}
+
+// `cargo afl build` sets `--cfg fuzzing`, and links in the AFL++ runtime.
+#[cfg(fuzzing)]
+propfuzz::afl_main!(__PROPFUZZ__reverse);
+
+// Other builds have no AFL++ runtime to link against, so the entry point is only type-checked.
+#[cfg(not(fuzzing))]
+#[allow(dead_code)]
+mod entry_point {
+    propfuzz::afl_main!(super::__PROPFUZZ__reverse);
+}
+
+#[cfg(not(fuzzing))]
+fn main() {}
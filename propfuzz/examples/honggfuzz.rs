@@ -0,0 +1,39 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Runs a propfuzz target under honggfuzz.
+//!
+//! Build with the following, then run the binary under `honggfuzz`:
+//!
+//! ```text
+//! cargo hfuzz build --example honggfuzz --features honggfuzz
+//! ```
+//!
+//! Without `cargo hfuzz`, the example builds, but its entry point is left out since there's no
+//! honggfuzz runtime to link against.
+
+use propfuzz::prelude::*;
+use proptest::collection::vec;
+
+/// Test that reversing a list twice produces the same results.
+#[propfuzz]
+fn reverse(#[propfuzz(strategy = "vec(any::<u32>(), 0..64)")] mut list: Vec<u32>) {
+    let list2 = list.clone();
+    list.reverse();
+    list.reverse();
+    prop_assert_eq!(list, list2);
+}
+
+// `cargo hfuzz build` sets `--cfg fuzzing`, and links in the honggfuzz runtime.
+#[cfg(fuzzing)]
+propfuzz::honggfuzz_main!(__PROPFUZZ__reverse);
+
+// Other builds have no honggfuzz runtime to link against, so the entry point is only type-checked.
+#[cfg(not(fuzzing))]
+#[allow(dead_code)]
+mod entry_point {
+    propfuzz::honggfuzz_main!(super::__PROPFUZZ__reverse);
+}
+
+#[cfg(not(fuzzing))]
+fn main() {}
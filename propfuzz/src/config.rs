@@ -0,0 +1,289 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Configuration through `propfuzz.toml` files.
+//!
+//! Setting the same options on every `#[propfuzz]` attribute quickly becomes unmanageable.
+//! Instead, default proptest config values can be set in a `propfuzz.toml` file. The file is found
+//! by walking up from the directory of the package being tested (`CARGO_MANIFEST_DIR`), so a
+//! single file at the root of a workspace applies to every crate in it.
+//!
+//! ## Examples
+//!
+//! ```toml
+//! # Values that apply to every target.
+//! [default]
+//! cases = 256
+//!
+//! # Values that apply to targets whose names match a glob pattern.
+//! [targets."my_crate::parser::*"]
+//! max_shrink_iters = 10000
+//!
+//! # Profiles, selected by setting PROPFUZZ_PROFILE.
+//! [profile.nightly]
+//! cases = 65536
+//!
+//! [profile.nightly.targets."my_crate::slow_*"]
+//! cases = 1024
+//! ```
+//!
+//! The supported keys are the ones that can be set through `#[propfuzz]`: `cases`,
+//! `max_local_rejects`, `max_global_rejects`, `max_flat_map_regens`, `fork`, `timeout`,
+//! `max_shrink_time`, `max_shrink_iters` and `verbose`.
+//!
+//! More specific sections take precedence: `[default]` is applied first, then the active
+//! `[profile.NAME]`, then `[targets."PATTERN"]`, then `[profile.NAME.targets."PATTERN"]`. If
+//! several patterns in a table match a target, longer patterns take precedence. Options set
+//! through `#[propfuzz]` attributes take precedence over the file, and environment variables (see
+//! [`runtime::apply_env_overrides`](../runtime/fn.apply_env_overrides.html)) take precedence over
+//! both.
+
+use glob::Pattern;
+use proptest::test_runner::Config;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+/// The name of the configuration file.
+pub const FILE_NAME: &str = "propfuzz.toml";
+
+/// The environment variable containing the name of the active profile.
+pub const PROFILE_ENV: &str = "PROPFUZZ_PROFILE";
+
+/// Returns the default proptest config for the named target, with values from the nearest
+/// `propfuzz.toml` and the profile in `PROPFUZZ_PROFILE` applied.
+///
+/// This is used by the code generated by `#[propfuzz]`, before options set through the attribute
+/// are applied. The file is read once per process.
+///
+/// # Panics
+///
+/// Panics if the file can't be read or parsed, or if it doesn't define the active profile.
+pub fn base_config(name: &str) -> Config {
+    let mut config = Config::default();
+    if let Some(loaded) = loaded() {
+        loaded
+            .file
+            .apply(name, loaded.profile.as_deref(), &mut config);
+    }
+    config
+}
+
+/// A parsed `propfuzz.toml` file.
+#[derive(Clone, Debug, Default)]
+pub struct ConfigFile {
+    default: Values,
+    targets: Targets,
+    profiles: BTreeMap<String, (Values, Targets)>,
+}
+
+impl ConfigFile {
+    /// Parses a configuration file from a string.
+    ///
+    /// Returns an error of kind `InvalidData` if the file is invalid.
+    pub fn parse(contents: &str) -> io::Result<Self> {
+        let raw: RawConfigFile = toml::from_str(contents).map_err(invalid_data)?;
+        let profiles = raw
+            .profile
+            .into_iter()
+            .map(|(name, profile)| {
+                let values = profile.values();
+                Ok((name, (values, Targets::new(profile.targets)?)))
+            })
+            .collect::<io::Result<_>>()?;
+        Ok(Self {
+            default: raw.default,
+            targets: Targets::new(raw.targets)?,
+            profiles,
+        })
+    }
+
+    /// Reads and parses the configuration file at `path`.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        Self::parse(&contents).map_err(|err| {
+            io::Error::new(err.kind(), format!("error in {}: {}", path.display(), err))
+        })
+    }
+
+    /// Looks for a configuration file in `dir` and its ancestors, returning the path to the
+    /// nearest one.
+    pub fn find(dir: &Path) -> Option<PathBuf> {
+        dir.ancestors()
+            .map(|dir| dir.join(FILE_NAME))
+            .find(|path| path.is_file())
+    }
+
+    /// Returns true if this file defines the given profile.
+    pub fn has_profile(&self, profile: &str) -> bool {
+        self.profiles.contains_key(profile)
+    }
+
+    /// Applies the values in this file for the named target to `config`, using the given profile.
+    ///
+    /// Profiles that aren't defined in this file are ignored.
+    pub fn apply(&self, name: &str, profile: Option<&str>, config: &mut Config) {
+        let profile = profile.and_then(|profile| self.profiles.get(profile));
+
+        self.default.apply(config);
+        if let Some((values, _)) = profile {
+            values.apply(config);
+        }
+        self.targets.apply(name, config);
+        if let Some((_, targets)) = profile {
+            targets.apply(name, config);
+        }
+    }
+}
+
+/// A loaded configuration file, along with the active profile.
+struct Loaded {
+    file: ConfigFile,
+    profile: Option<String>,
+}
+
+/// The configuration file for this process, or null if it hasn't been loaded yet.
+static LOADED: AtomicPtr<Option<Loaded>> = AtomicPtr::new(ptr::null_mut());
+
+fn loaded() -> Option<&'static Loaded> {
+    let mut current = LOADED.load(Ordering::Acquire);
+    if current.is_null() {
+        let new = Box::into_raw(Box::new(load()));
+        current = match LOADED.compare_exchange(
+            ptr::null_mut(),
+            new,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => new,
+            Err(existing) => {
+                // Another thread got there first.
+                // Safety: `new` was never shared.
+                drop(unsafe { Box::from_raw(new) });
+                existing
+            }
+        };
+    }
+    // Safety: once set, the pointer is never changed or freed.
+    unsafe { (*current).as_ref() }
+}
+
+fn load() -> Option<Loaded> {
+    let dir = match env::var_os("CARGO_MANIFEST_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => env::current_dir().ok()?,
+    };
+    let path = ConfigFile::find(&dir)?;
+    let file = ConfigFile::load(&path).unwrap_or_else(|err| panic!("propfuzz: {}", err));
+
+    let profile = env::var(PROFILE_ENV).ok();
+    if let Some(profile) = &profile {
+        if !file.has_profile(profile) {
+            panic!(
+                "propfuzz: profile '{}' (from {}) is not defined in {}",
+                profile,
+                PROFILE_ENV,
+                path.display()
+            );
+        }
+    }
+    Some(Loaded { file, profile })
+}
+
+/// Target name patterns, sorted so that longer patterns are applied last.
+#[derive(Clone, Debug, Default)]
+struct Targets(Vec<(Pattern, Values)>);
+
+impl Targets {
+    fn new(raw: BTreeMap<String, Values>) -> io::Result<Self> {
+        let mut targets = raw
+            .into_iter()
+            .map(|(pattern, values)| {
+                let pattern = Pattern::new(&pattern).map_err(|err| {
+                    invalid_data(format!("invalid target pattern '{}': {}", pattern, err))
+                })?;
+                Ok((pattern, values))
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        targets.sort_by_key(|(pattern, _)| pattern.as_str().len());
+        Ok(Self(targets))
+    }
+
+    fn apply(&self, name: &str, config: &mut Config) {
+        for (pattern, values) in &self.0 {
+            if pattern.matches(name) {
+                values.apply(config);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawConfigFile {
+    #[serde(default)]
+    default: Values,
+    #[serde(default)]
+    targets: BTreeMap<String, Values>,
+    #[serde(default)]
+    profile: BTreeMap<String, RawProfile>,
+}
+
+macro_rules! config_values {
+    ($($field:ident: $ty:ty,)*) => {
+        /// Proptest config values set in a section of the file.
+        #[derive(Clone, Debug, Default, Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct Values {
+            $($field: Option<$ty>,)*
+        }
+
+        impl Values {
+            fn apply(&self, config: &mut Config) {
+                $(
+                    if let Some(value) = self.$field {
+                        config.$field = value;
+                    }
+                )*
+            }
+        }
+
+        /// A profile section, which can also contain target patterns.
+        #[derive(Debug, Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct RawProfile {
+            $($field: Option<$ty>,)*
+            #[serde(default)]
+            targets: BTreeMap<String, Values>,
+        }
+
+        impl RawProfile {
+            fn values(&self) -> Values {
+                Values {
+                    $($field: self.$field,)*
+                }
+            }
+        }
+    };
+}
+
+config_values! {
+    cases: u32,
+    max_local_rejects: u32,
+    max_global_rejects: u32,
+    max_flat_map_regens: u32,
+    fork: bool,
+    timeout: u32,
+    max_shrink_time: u32,
+    max_shrink_iters: u32,
+    verbose: u32,
+}
+
+fn invalid_data(err: impl ToString) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+}
@@ -0,0 +1,94 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Support for generating seed corpora.
+//!
+//! Coverage-guided fuzzers are much more effective when started off with a corpus of well-formed
+//! inputs. `propfuzz` can produce such a corpus from `proptest` strategies: see
+//! [`runtime::generate_corpus`](../runtime/fn.generate_corpus.html).
+//!
+//! Structured targets need no extra setup: the random bytes used to generate each value are
+//! recorded, and used as inputs.
+//!
+//! ## Examples
+//!
+//! For an unstructured target, a corpus generator produces serialized values:
+//!
+//! ```
+//! use propfuzz::prelude::*;
+//!
+//! fn generate(generator: &mut ValueGenerator) -> Vec<u8> {
+//!     // Generate a new value using a proptest strategy.
+//!     let value = generator.new_value(any::<u32>());
+//!     // Serialize this value into a byte array.
+//!     value.to_le_bytes().to_vec()
+//! }
+//!
+//! /// Decoding arbitrary bytes doesn't panic.
+//! #[propfuzz(corpus_generator = "generate")]
+//! fn decode(data: &[u8]) {
+//!     if data.len() == 4 {
+//!         let _ = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
+//!     }
+//! }
+//! ```
+
+use proptest::strategy::{Strategy, ValueTree};
+use proptest::test_runner::TestRunner;
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Generates random values from `proptest` strategies.
+///
+/// This is passed in to corpus generators specified through
+/// `#[propfuzz(corpus_generator = "...")]`.
+#[derive(Debug, Default)]
+pub struct ValueGenerator {
+    test_runner: TestRunner,
+}
+
+impl ValueGenerator {
+    /// Creates a new value generator with a random seed.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new value generator with a fixed seed, so that it always produces the same
+    /// sequence of values.
+    pub fn deterministic() -> Self {
+        Self::from_test_runner(TestRunner::deterministic())
+    }
+
+    /// Creates a new value generator that uses the given test runner.
+    pub fn from_test_runner(test_runner: TestRunner) -> Self {
+        Self { test_runner }
+    }
+
+    /// Generates a new value using the given strategy.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the strategy fails to generate a value, e.g. because it rejected too many values.
+    pub fn new_value<S: Strategy>(&mut self, strategy: S) -> S::Value {
+        strategy
+            .new_tree(&mut self.test_runner)
+            .expect("creating a new value should succeed")
+            .current()
+    }
+}
+
+/// Writes out an input to `dir`, naming it after a hash of its contents.
+///
+/// Identical inputs end up at the same path, so the corpus doesn't contain duplicates.
+pub(crate) fn write_input(dir: &Path, prefix: &str, data: &[u8]) -> io::Result<PathBuf> {
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    let path = dir.join(format!("{}{:016x}", prefix, hasher.finish()));
+
+    fs::create_dir_all(dir)?;
+    fs::write(&path, data)?;
+    Ok(path)
+}
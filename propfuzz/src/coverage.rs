@@ -0,0 +1,182 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Coverage feedback for the [in-process fuzz loop](../engine/index.html).
+//!
+//! With the `coverage` feature enabled, `propfuzz` receives the coverage counters that LLVM's
+//! SanitizerCoverage instrumentation inserts into every function. Binaries must be built with:
+//!
+//! ```text
+//! RUSTFLAGS="-Cpasses=sancov-module \
+//!     -Cllvm-args=-sanitizer-coverage-level=3 \
+//!     -Cllvm-args=-sanitizer-coverage-inline-8bit-counters" \
+//!     cargo test --no-run --target <host triple>
+//! ```
+//!
+//! Passing in `--target` keeps build scripts and procedural macros from being instrumented. This
+//! works on stable Rust, and is what `cargo propfuzz fuzz --coverage` does.
+//!
+//! The `coverage` feature defines the same callbacks as libFuzzer, so it can't be used in binaries
+//! that also link in libFuzzer. Without the feature, or in binaries that aren't instrumented,
+//! [`is_enabled`](fn.is_enabled.html) returns false and the fuzz loop falls back to generating
+//! random inputs.
+
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+/// Returns true if coverage counters are available in this binary.
+pub fn is_enabled() -> bool {
+    !REGIONS.load(Ordering::Acquire).is_null()
+}
+
+/// Tracks the coverage seen so far across runs.
+///
+/// Following libFuzzer, each counter contributes one feature per bucket of hit counts it has been
+/// seen in (1, 2, 3, 4-7, 8-15, 16-31, 32-127 and 128+), so that inputs that run a loop more times
+/// are also considered interesting.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct CoverageMap {
+    seen: Vec<u8>,
+    features: usize,
+}
+
+impl CoverageMap {
+    /// Returns the number of features seen so far.
+    pub(crate) fn features(&self) -> usize {
+        self.features
+    }
+
+    /// Resets all counters, ahead of a new run.
+    pub(crate) fn reset_counters(&self) {
+        for_each_region(Counters::reset);
+    }
+
+    /// Records the counters for the last run, returning true if it produced any new features.
+    pub(crate) fn update(&mut self) -> bool {
+        let seen = &mut self.seen;
+        let mut features = 0;
+        let mut index = 0;
+        for_each_region(|counters| {
+            if seen.len() < index + counters.len() {
+                seen.resize(index + counters.len(), 0);
+            }
+            for (i, buckets) in seen[index..index + counters.len()].iter_mut().enumerate() {
+                let bucket = bucket(counters.get(i));
+                if *buckets & bucket != bucket {
+                    *buckets |= bucket;
+                    features += 1;
+                }
+            }
+            index += counters.len();
+        });
+
+        self.features += features;
+        features > 0
+    }
+}
+
+fn bucket(count: u8) -> u8 {
+    match count {
+        0 => 0,
+        1 => 1 << 0,
+        2 => 1 << 1,
+        3 => 1 << 2,
+        4..=7 => 1 << 3,
+        8..=15 => 1 << 4,
+        16..=31 => 1 << 5,
+        32..=127 => 1 << 6,
+        128..=255 => 1 << 7,
+    }
+}
+
+/// A region of counters registered by the instrumentation, one per instrumented module.
+///
+/// Modules are registered from static constructors that run before `main`, so regions are kept in
+/// a lock-free list that needs no initialization, and that is only ever added to.
+struct Region {
+    start: *mut u8,
+    len: usize,
+    next: *mut Region,
+}
+
+static REGIONS: AtomicPtr<Region> = AtomicPtr::new(ptr::null_mut());
+
+fn for_each_region(mut f: impl FnMut(Counters)) {
+    let mut region = REGIONS.load(Ordering::Acquire);
+    while !region.is_null() {
+        // Safety: regions are never freed, and are only ever added to the front of the list.
+        unsafe {
+            f(Counters {
+                start: (*region).start,
+                len: (*region).len,
+            });
+            region = (*region).next;
+        }
+    }
+}
+
+/// The counters in a region.
+///
+/// Instrumented code bumps counters on every thread, including other tests run by libtest, so
+/// counters are never borrowed. They are read and reset one at a time through volatile accesses
+/// instead, the same way the instrumentation accesses them. Updates from other threads can still
+/// be lost or show up in the next run, which only makes the coverage seen a bit noisier.
+#[derive(Clone, Copy)]
+struct Counters {
+    start: *mut u8,
+    len: usize,
+}
+
+impl Counters {
+    fn len(self) -> usize {
+        self.len
+    }
+
+    fn get(self, index: usize) -> u8 {
+        assert!(index < self.len, "counter index out of bounds");
+        // Safety: counters live for the rest of the program, and index is in bounds.
+        unsafe { ptr::read_volatile(self.start.add(index)) }
+    }
+
+    fn reset(self) {
+        for index in 0..self.len {
+            // Safety: counters live for the rest of the program, and index is in bounds.
+            unsafe { ptr::write_volatile(self.start.add(index), 0) };
+        }
+    }
+}
+
+#[cfg(feature = "coverage")]
+fn register(start: *mut u8, end: *mut u8) {
+    let len = end as usize - start as usize;
+    if len == 0 {
+        return;
+    }
+    let region = Box::into_raw(Box::new(Region {
+        start,
+        len,
+        next: ptr::null_mut(),
+    }));
+
+    let mut head = REGIONS.load(Ordering::Acquire);
+    loop {
+        // Safety: the region was just allocated, and isn't visible to other threads yet.
+        unsafe { (*region).next = head };
+        match REGIONS.compare_exchange_weak(head, region, Ordering::AcqRel, Ordering::Acquire) {
+            Ok(_) => break,
+            Err(current) => head = current,
+        }
+    }
+}
+
+/// Called by the instrumentation with the bounds of each module's counters.
+#[cfg(feature = "coverage")]
+#[no_mangle]
+pub extern "C" fn __sanitizer_cov_8bit_counters_init(start: *mut u8, end: *mut u8) {
+    register(start, end);
+}
+
+/// Called by the instrumentation with the bounds of each module's PC table, if enabled. Unused.
+#[cfg(feature = "coverage")]
+#[no_mangle]
+pub extern "C" fn __sanitizer_cov_pcs_init(_start: *const usize, _end: *const usize) {}
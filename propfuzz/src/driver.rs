@@ -0,0 +1,295 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Support for running propfuzz targets through `cargo propfuzz`.
+//!
+//! `cargo propfuzz` builds test binaries as usual, then runs the test defined by
+//! [`propfuzz::driver!`](../macro.driver.html) in each of them, with the `PROPFUZZ_DRIVER`
+//! environment variable set to a command. The test runs the command against the
+//! [registry](../registry/index.html) and exits the process. If the variable isn't set, the test
+//! does nothing, so it passes under `cargo test`.
+//!
+//! Test binaries that don't invoke `propfuzz::driver!` at their crate root are skipped by
+//! `cargo propfuzz`.
+//!
+//! The supported commands are:
+//! * `list`: print one line per target, in the form
+//!   `propfuzz-target\t<name>\t<fuzz_default>\t<description>`, where `fuzz_default` is `true` or
+//!   `false`. Only the first line of the description is printed.
+//! * `test`: run the target named by `PROPFUZZ_DRIVER_TARGET` as a standard property-based test.
+//! * `fuzz`: run the target named by `PROPFUZZ_DRIVER_TARGET` under the
+//!   [in-process fuzz loop](../engine/index.html). The loop is configured through the
+//!   `PROPFUZZ_DRIVER_RUNS`, `PROPFUZZ_DRIVER_MAX_TOTAL_TIME` (in seconds),
+//!   `PROPFUZZ_DRIVER_MAX_LEN`, `PROPFUZZ_DRIVER_SEED` and `PROPFUZZ_DRIVER_ARTIFACT_DIR`
+//!   environment variables.
+//! * `replay`: replay the input in the file `PROPFUZZ_DRIVER_INPUT` against the target named by
+//!   `PROPFUZZ_DRIVER_TARGET`, reporting any failure the same way as property-based tests.
+//! * `shrink`: like `replay`, but shrink the input to a minimal failing value if it fails.
+//! * `corpus`: generate a seed corpus for the target named by `PROPFUZZ_DRIVER_TARGET`, writing
+//!   `PROPFUZZ_DRIVER_COUNT` inputs into `PROPFUZZ_DRIVER_CORPUS_DIR`.
+//!
+//! The process exits with code 0 on success, 1 if a test failed, and 2 if the command was invalid.
+
+use crate::coverage;
+use crate::engine::{self, FuzzOptions, FuzzStats};
+use crate::registry::{self, RegisteredTarget};
+use std::env;
+use std::fmt;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// The environment variable containing the command to run.
+pub const DRIVER_ENV: &str = "PROPFUZZ_DRIVER";
+
+/// The environment variable containing the name of the target to run.
+pub const TARGET_ENV: &str = "PROPFUZZ_DRIVER_TARGET";
+
+/// The environment variable containing the maximum number of inputs to fuzz.
+pub const RUNS_ENV: &str = "PROPFUZZ_DRIVER_RUNS";
+
+/// The environment variable containing the maximum number of seconds to fuzz for.
+pub const MAX_TOTAL_TIME_ENV: &str = "PROPFUZZ_DRIVER_MAX_TOTAL_TIME";
+
+/// The environment variable containing the maximum length of fuzz inputs.
+pub const MAX_LEN_ENV: &str = "PROPFUZZ_DRIVER_MAX_LEN";
+
+/// The environment variable containing the seed for the fuzz loop.
+pub const SEED_ENV: &str = "PROPFUZZ_DRIVER_SEED";
+
+/// The environment variable containing the directory to write failing fuzz inputs to.
+pub const ARTIFACT_DIR_ENV: &str = "PROPFUZZ_DRIVER_ARTIFACT_DIR";
+
+/// The environment variable containing the path to an input to replay.
+pub const INPUT_ENV: &str = "PROPFUZZ_DRIVER_INPUT";
+
+/// The environment variable containing the directory to write a seed corpus to.
+pub const CORPUS_DIR_ENV: &str = "PROPFUZZ_DRIVER_CORPUS_DIR";
+
+/// The environment variable containing the number of inputs to generate for a seed corpus.
+pub const COUNT_ENV: &str = "PROPFUZZ_DRIVER_COUNT";
+
+/// The prefix for every line printed by the `list` command.
+pub const LIST_PREFIX: &str = "propfuzz-target";
+
+/// The name of the test defined by [`propfuzz::driver!`](../macro.driver.html).
+pub const TEST_NAME: &str = "__propfuzz_driver";
+
+// Not public API. Called by the test defined by propfuzz::driver!.
+#[doc(hidden)]
+pub fn __run_from_env() {
+    if let Ok(command) = env::var(DRIVER_ENV) {
+        process::exit(run(&command));
+    }
+}
+
+fn run(command: &str) -> i32 {
+    match command {
+        "list" => {
+            for target in registry::all_targets() {
+                let description = target
+                    .description()
+                    .and_then(|description| description.lines().next())
+                    .unwrap_or("");
+                println!(
+                    "{}\t{}\t{}\t{}",
+                    LIST_PREFIX,
+                    target.name(),
+                    target.fuzz_default(),
+                    description
+                );
+            }
+            0
+        }
+        "test" => match find_target() {
+            Ok(target) => {
+                match panic::catch_unwind(AssertUnwindSafe(|| target.execute_as_proptest())) {
+                    Ok(()) => 0,
+                    // The panic message has already been printed by the panic hook.
+                    Err(_) => 1,
+                }
+            }
+            Err(code) => code,
+        },
+        "fuzz" => match (find_target(), fuzz_options()) {
+            (Ok(target), Ok(options)) => run_fuzz(target, &options),
+            (Err(code), _) | (_, Err(code)) => code,
+        },
+        "replay" | "shrink" => match (find_target(), env::var_os(INPUT_ENV)) {
+            (Ok(target), Some(input)) => run_replay(target, Path::new(&input), command == "shrink"),
+            (Ok(_), None) => {
+                eprintln!("propfuzz: {} must be set", INPUT_ENV);
+                2
+            }
+            (Err(code), _) => code,
+        },
+        "corpus" => match (find_target(), corpus_options()) {
+            (Ok(target), Ok((dir, count))) => run_corpus(target, &dir, count),
+            (Err(code), _) | (_, Err(code)) => code,
+        },
+        _ => {
+            eprintln!("propfuzz: unknown driver command '{}'", command);
+            2
+        }
+    }
+}
+
+fn find_target() -> Result<&'static dyn RegisteredTarget, i32> {
+    let name = env::var(TARGET_ENV).map_err(|_| {
+        eprintln!("propfuzz: {} must be set", TARGET_ENV);
+        2
+    })?;
+    registry::find_target(&name).ok_or_else(|| {
+        eprintln!("propfuzz: target '{}' not found", name);
+        2
+    })
+}
+
+fn fuzz_options() -> Result<FuzzOptions, i32> {
+    let mut options = FuzzOptions::default();
+    if let Some(runs) = read_env(RUNS_ENV)? {
+        options.runs = Some(runs);
+    }
+    if let Some(secs) = read_env(MAX_TOTAL_TIME_ENV)? {
+        options.max_total_time = Some(Duration::from_secs(secs));
+    }
+    if let Some(max_len) = read_env(MAX_LEN_ENV)? {
+        options.max_len = max_len;
+    }
+    if let Some(seed) = read_env(SEED_ENV)? {
+        options.seed = seed;
+    }
+    if let Some(artifact_dir) = env::var_os(ARTIFACT_DIR_ENV) {
+        options.artifact_dir = Some(artifact_dir.into());
+    }
+    Ok(options)
+}
+
+fn corpus_options() -> Result<(PathBuf, usize), i32> {
+    let dir = env::var_os(CORPUS_DIR_ENV).ok_or_else(|| {
+        eprintln!("propfuzz: {} must be set", CORPUS_DIR_ENV);
+        2
+    })?;
+    let count = read_env(COUNT_ENV)?.ok_or_else(|| {
+        eprintln!("propfuzz: {} must be set", COUNT_ENV);
+        2
+    })?;
+    Ok((dir.into(), count))
+}
+
+fn read_env<T: FromStr>(var: &str) -> Result<Option<T>, i32> {
+    match env::var(var) {
+        Ok(value) => value.parse().map(Some).map_err(|_| {
+            eprintln!("propfuzz: invalid value for {}: '{}'", var, value);
+            2
+        }),
+        Err(_) => Ok(None),
+    }
+}
+
+fn run_fuzz(target: &dyn RegisteredTarget, options: &FuzzOptions) -> i32 {
+    eprintln!(
+        "propfuzz: fuzzing {} (seed {}, {})",
+        target.name(),
+        options.seed,
+        if coverage::is_enabled() {
+            "coverage-guided"
+        } else {
+            "no coverage feedback"
+        }
+    );
+    match engine::fuzz(target, options) {
+        Ok(Ok(stats)) => {
+            eprintln!("propfuzz: no failures found after {}", DisplayStats(&stats));
+            0
+        }
+        Ok(Err(crash)) => {
+            eprintln!(
+                "propfuzz: failure found after {}",
+                DisplayStats(&crash.stats)
+            );
+            if let Some(artifact) = &crash.artifact {
+                eprintln!(
+                    "propfuzz: failing input written to {}\n\
+                     propfuzz: shrink it with `cargo propfuzz shrink {} {}`",
+                    artifact.display(),
+                    target.name(),
+                    artifact.display()
+                );
+            }
+            1
+        }
+        Err(err) => {
+            eprintln!("propfuzz: error while fuzzing: {}", err);
+            1
+        }
+    }
+}
+
+struct DisplayStats<'a>(&'a FuzzStats);
+
+impl<'a> fmt::Display for DisplayStats<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let stats = self.0;
+        write!(
+            f,
+            "{} runs in {:.1}s",
+            stats.runs,
+            stats.elapsed.as_secs_f64()
+        )?;
+        if coverage::is_enabled() {
+            write!(
+                f,
+                " ({} coverage features, {} inputs in corpus)",
+                stats.features, stats.corpus_size
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn run_replay(target: &dyn RegisteredTarget, input: &Path, shrink: bool) -> i32 {
+    let data = match fs::read(input) {
+        Ok(data) => data,
+        Err(err) => {
+            eprintln!("propfuzz: error reading {}: {}", input.display(), err);
+            return 2;
+        }
+    };
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        if shrink {
+            target.shrink(&data)
+        } else {
+            target.replay(&data)
+        }
+    }));
+    match result {
+        Ok(()) => {
+            eprintln!("propfuzz: {} passed for {}", target.name(), input.display());
+            0
+        }
+        // The panic message has already been printed by the panic hook.
+        Err(_) => 1,
+    }
+}
+
+fn run_corpus(target: &dyn RegisteredTarget, dir: &Path, count: usize) -> i32 {
+    match target.generate_corpus(dir, count) {
+        Ok(paths) => {
+            eprintln!(
+                "propfuzz: wrote {} inputs for {} to {}",
+                paths.len(),
+                target.name(),
+                dir.display()
+            );
+            0
+        }
+        Err(err) => {
+            eprintln!("propfuzz: error generating corpus: {}", err);
+            1
+        }
+    }
+}
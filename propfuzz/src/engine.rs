@@ -0,0 +1,266 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! A simple in-process fuzz loop.
+//!
+//! This engine repeatedly runs a propfuzz target against byte inputs through
+//! [`execute_as_fuzz_target`](../runtime/fn.execute_as_fuzz_target.html), stopping at the first
+//! failure. It requires no external tooling, which makes it convenient for quick local runs
+//! through `cargo propfuzz fuzz`.
+//!
+//! If [coverage feedback](../coverage/index.html) is available, the engine is coverage-guided:
+//! inputs that reach new code are kept in an in-memory corpus, and most new inputs are produced
+//! by mutating inputs from the corpus. Otherwise, every input is generated at random.
+//!
+//! Either way, the target's examples are run first. Those that can be turned into fuzzer input (see
+//! [`example_inputs`](../runtime/fn.example_inputs.html)) are run as the first inputs, and with
+//! coverage feedback, they also seed the corpus. The others, which includes every example for
+//! structured targets, are run directly beforehand.
+
+use crate::coverage::{self, CoverageMap};
+use crate::{corpus, registry::RegisteredTarget, runtime};
+use proptest::prelude::RngCore;
+use proptest::test_runner::{RngAlgorithm, TestRng};
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Options for a fuzz run.
+#[derive(Clone, Debug)]
+pub struct FuzzOptions {
+    /// The maximum number of inputs to run. If `None`, there is no limit.
+    pub runs: Option<u64>,
+
+    /// The maximum amount of time to fuzz for. If `None`, there is no limit.
+    pub max_total_time: Option<Duration>,
+
+    /// The maximum length of a generated input, in bytes.
+    pub max_len: usize,
+
+    /// The seed for the random number generator used to generate inputs.
+    pub seed: u64,
+
+    /// The directory to write failing inputs to. If `None`, failing inputs are not written out.
+    pub artifact_dir: Option<PathBuf>,
+}
+
+impl Default for FuzzOptions {
+    fn default() -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos() as u64)
+            .unwrap_or(0);
+        Self {
+            runs: None,
+            max_total_time: None,
+            max_len: 4096,
+            seed,
+            artifact_dir: None,
+        }
+    }
+}
+
+/// Statistics about a fuzz run.
+#[derive(Clone, Debug)]
+pub struct FuzzStats {
+    /// The number of inputs that were run.
+    pub runs: u64,
+
+    /// The time spent fuzzing.
+    pub elapsed: Duration,
+
+    /// The number of inputs in the in-memory corpus. Always 0 without coverage feedback.
+    pub corpus_size: usize,
+
+    /// The number of coverage features seen. Always 0 without coverage feedback.
+    pub features: usize,
+}
+
+/// A failing input found by the fuzz loop.
+#[derive(Clone, Debug)]
+pub struct Crash {
+    /// The input that caused the failure.
+    ///
+    /// This is empty if an example that can't be turned into fuzzer input failed.
+    pub input: Vec<u8>,
+
+    /// The failure message.
+    pub message: String,
+
+    /// The path the input was written to, if an artifact directory was specified and the failure
+    /// wasn't in an example that can't be turned into fuzzer input.
+    pub artifact: Option<PathBuf>,
+
+    /// Statistics about the fuzz run up to and including the failure.
+    pub stats: FuzzStats,
+}
+
+/// Fuzzes the given target until a failure is found or one of the limits in `options` is reached.
+///
+/// Returns statistics about the run if no failures were found, or the first failing input.
+pub fn fuzz(
+    target: &dyn RegisteredTarget,
+    options: &FuzzOptions,
+) -> io::Result<Result<FuzzStats, Crash>> {
+    let mut rng = seeded_rng(options.seed);
+    let start = Instant::now();
+    let mut runs = 0;
+    let mut input = Vec::with_capacity(options.max_len);
+
+    let guided = coverage::is_enabled();
+    let mut coverage = CoverageMap::default();
+    let mut corpus: Vec<Vec<u8>> = vec![];
+    let examples = target.example_inputs();
+    let config = target.fuzz_config();
+    let stats = |runs, corpus: &[Vec<u8>], coverage: &CoverageMap| FuzzStats {
+        runs,
+        elapsed: start.elapsed(),
+        corpus_size: corpus.len(),
+        features: coverage.features(),
+    };
+
+    // Examples that can't be turned into fuzzer input are run directly, before anything else.
+    if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| target.execute_examples())) {
+        return Ok(Err(Crash {
+            input: vec![],
+            message: runtime::panic_message(&*payload),
+            artifact: None,
+            stats: stats(runs, &corpus, &coverage),
+        }));
+    }
+
+    loop {
+        if matches!(options.runs, Some(max_runs) if runs >= max_runs) {
+            break;
+        }
+        if matches!(options.max_total_time, Some(max_total_time) if start.elapsed() >= max_total_time)
+        {
+            break;
+        }
+
+        // Examples go first. After that, keep generating some random inputs, so the search doesn't
+        // get stuck around the corpus.
+        let example = examples.get(runs as usize);
+        if let Some(example) = example {
+            input.clear();
+            input.extend_from_slice(example);
+        } else if !corpus.is_empty() && index(&mut rng, 8) != 0 {
+            let base = &corpus[index(&mut rng, corpus.len())];
+            input.clear();
+            input.extend_from_slice(base);
+            mutate(&mut rng, &mut input, &corpus, options.max_len);
+        } else {
+            let len = (rng.next_u64() % (options.max_len as u64 + 1)) as usize;
+            input.resize(len, 0);
+            rng.fill_bytes(&mut input);
+        }
+        runs += 1;
+
+        if guided {
+            coverage.reset_counters();
+        }
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            target.execute_as_fuzz_target_with_config(&config, &input)
+        }));
+        if guided && (coverage.update() || example.is_some()) {
+            corpus.push(input.clone());
+        }
+        if let Err(payload) = result {
+            let artifact = match &options.artifact_dir {
+                Some(dir) => Some(corpus::write_input(dir, "crash-", &input)?),
+                None => None,
+            };
+            return Ok(Err(Crash {
+                input,
+                message: runtime::panic_message(&*payload),
+                artifact,
+                stats: stats(runs, &corpus, &coverage),
+            }));
+        }
+    }
+
+    Ok(Ok(stats(runs, &corpus, &coverage)))
+}
+
+/// Values that commonly trigger edge cases.
+const INTERESTING_BYTES: &[u8] = &[0, 1, 0x7f, 0x80, 0xff];
+
+/// The maximum number of bytes inserted or removed by a single mutation.
+const MAX_MUTATION_LEN: usize = 8;
+
+/// Applies a few random mutations to `input`, keeping it within `max_len` bytes.
+///
+/// Since inputs are used as a pass-through RNG, small changes to the bytes usually result in small
+/// changes to the generated value.
+fn mutate(rng: &mut TestRng, input: &mut Vec<u8>, corpus: &[Vec<u8>], max_len: usize) {
+    let count = 1 + rng.next_u32() % 4;
+    for _ in 0..count {
+        let len = input.len();
+        match rng.next_u32() % 7 {
+            // Flip a bit.
+            0 if len > 0 => {
+                let i = index(rng, len);
+                input[i] ^= 1 << (rng.next_u32() % 8);
+            }
+            // Replace a byte with a random one.
+            1 if len > 0 => {
+                let i = index(rng, len);
+                input[i] = rng.next_u32() as u8;
+            }
+            // Replace a byte with an interesting value.
+            2 if len > 0 => {
+                let i = index(rng, len);
+                input[i] = INTERESTING_BYTES[index(rng, INTERESTING_BYTES.len())];
+            }
+            // Add a small delta to a byte.
+            3 if len > 0 => {
+                let i = index(rng, len);
+                let delta = 1 + index(rng, 16) as u8;
+                input[i] = if index(rng, 2) == 0 {
+                    input[i].wrapping_add(delta)
+                } else {
+                    input[i].wrapping_sub(delta)
+                };
+            }
+            // Remove a range of bytes.
+            4 if len > 0 => {
+                let i = index(rng, len);
+                let n = 1 + index(rng, MAX_MUTATION_LEN.min(len - i));
+                input.drain(i..i + n);
+            }
+            // Copy a range of bytes from another input in the corpus.
+            5 => {
+                let other = &corpus[index(rng, corpus.len())];
+                if !other.is_empty() && len < max_len {
+                    let from = index(rng, other.len());
+                    let n = 1 + index(rng, (other.len() - from).min(max_len - len));
+                    let at = index(rng, len + 1);
+                    input.splice(at..at, other[from..from + n].iter().copied());
+                }
+            }
+            // Insert random bytes.
+            _ if len < max_len => {
+                let at = index(rng, len + 1);
+                let n = 1 + index(rng, MAX_MUTATION_LEN.min(max_len - len));
+                let mut bytes = [0; MAX_MUTATION_LEN];
+                rng.fill_bytes(&mut bytes[..n]);
+                input.splice(at..at, bytes[..n].iter().copied());
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Returns a random index less than `len`, which must be nonzero.
+fn index(rng: &mut TestRng, len: usize) -> usize {
+    (rng.next_u64() % len as u64) as usize
+}
+
+fn seeded_rng(seed: u64) -> TestRng {
+    // XorShift requires a nonzero seed, which the second half always is for a zero seed.
+    let mut seed_bytes = [0; 16];
+    seed_bytes[..8].copy_from_slice(&seed.to_le_bytes());
+    seed_bytes[8..].copy_from_slice(&(!seed).to_le_bytes());
+    TestRng::from_seed(RngAlgorithm::XorShift, &seed_bytes)
+}
@@ -0,0 +1,102 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Executors for `async fn` targets.
+//!
+//! `#[propfuzz]` supports `async fn`s by blocking on the body of the test once per case. By
+//! default, this uses the minimal executor in [`block_on`](fn.block_on.html), which is enough for
+//! futures that don't depend on a particular runtime. Futures that need a runtime, such as ones
+//! that do I/O through `tokio`, can pick a different executor with the `runtime` argument:
+//!
+//! * `runtime = "builtin"`: [`block_on`](fn.block_on.html) (the default).
+//! * `runtime = "tokio"`: [`tokio::block_on`](tokio/fn.block_on.html), which requires the `tokio`
+//!   feature.
+
+use std::future::Future;
+use std::mem;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+use std::thread::{self, Thread};
+
+/// Runs a future to completion on the current thread.
+///
+/// The thread is parked while the future is pending, and unparked when it is woken.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let signal = Arc::new(Signal {
+        woken: AtomicBool::new(false),
+        thread: thread::current(),
+    });
+    // Safety: the vtable functions uphold the RawWaker contract for an Arc<Signal>.
+    let waker = unsafe { Waker::from_raw(raw_waker(signal.clone())) };
+    let mut cx = Context::from_waker(&waker);
+
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        // Parking can wake up spuriously, so wait until the future has actually been woken.
+        while !signal.woken.swap(false, Ordering::AcqRel) {
+            thread::park();
+        }
+    }
+}
+
+/// Shared state between `block_on` and its waker.
+struct Signal {
+    woken: AtomicBool,
+    thread: Thread,
+}
+
+impl Signal {
+    fn wake(&self) {
+        self.woken.store(true, Ordering::Release);
+        self.thread.unpark();
+    }
+}
+
+static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, wake, wake_by_ref, drop);
+
+fn raw_waker(signal: Arc<Signal>) -> RawWaker {
+    RawWaker::new(Arc::into_raw(signal) as *const (), &VTABLE)
+}
+
+unsafe fn clone(data: *const ()) -> RawWaker {
+    let signal = Arc::from_raw(data as *const Signal);
+    let cloned = signal.clone();
+    // The waker being cloned still owns its reference.
+    mem::forget(signal);
+    raw_waker(cloned)
+}
+
+unsafe fn wake(data: *const ()) {
+    Arc::from_raw(data as *const Signal).wake();
+}
+
+unsafe fn wake_by_ref(data: *const ()) {
+    (*(data as *const Signal)).wake();
+}
+
+unsafe fn drop(data: *const ()) {
+    mem::drop(Arc::from_raw(data as *const Signal));
+}
+
+/// Support for the [`tokio`](https://tokio.rs) runtime. Requires the `tokio` feature.
+#[cfg(feature = "tokio")]
+pub mod tokio {
+    use std::future::Future;
+
+    /// Runs a future to completion on a new single-threaded `tokio` runtime.
+    ///
+    /// A new runtime is created for every call, so that tasks spawned by one test case can't
+    /// affect the next one. All drivers enabled through `tokio`'s features, such as I/O and time,
+    /// are available.
+    pub fn block_on<F: Future>(future: F) -> F::Output {
+        ::tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("failed to build tokio runtime")
+            .block_on(future)
+    }
+}
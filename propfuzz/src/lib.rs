@@ -6,12 +6,167 @@
 //! For more, see the [`README`](https://github.com/facebookincubator/propfuzz/blob/main/README.md)
 //! at the root of the `propfuzz` repository.
 
+pub mod config;
+pub mod corpus;
+pub mod coverage;
+pub mod driver;
+pub mod engine;
+pub mod executor;
+pub mod persistence;
 pub mod prelude;
+pub mod registry;
+pub mod rng;
 pub mod runtime;
 pub mod traits;
 
-// Re-export the propfuzz macro -- this is expected to be the primary interface.
+// Re-export the propfuzz macros -- these are expected to be the primary interface.
 #[cfg(feature = "macro")]
-pub use propfuzz_macro::propfuzz;
+pub use propfuzz_macro::{propfuzz, propfuzz_suite};
 
 pub use proptest;
+
+/// Defines the test that `cargo propfuzz` runs to list, test and fuzz the targets in a test binary.
+///
+/// This must be invoked once, at the root of each test binary that `cargo propfuzz` should pick
+/// up: in `lib.rs` for unit tests, and in the top-level file of each integration test. The test
+/// does nothing when run through `cargo test`. See the [`driver`](driver/index.html) module for
+/// more.
+///
+/// ## Examples
+///
+/// ```
+/// use propfuzz::prelude::*;
+///
+/// propfuzz::driver!();
+///
+/// /// Test that adding zero is the identity.
+/// #[propfuzz]
+/// fn add_zero(x: u64) {
+///     prop_assert_eq!(x + 0, x);
+/// }
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! driver {
+    () => {
+        #[test]
+        fn __propfuzz_driver() {
+            $crate::driver::__run_from_env();
+        }
+    };
+}
+
+/// Generates a `main` function that runs a propfuzz target under AFL++.
+///
+/// The argument is an expression for the target, which is a unit struct named after the test
+/// function with a `__PROPFUZZ__` prefix. See
+/// [`runtime::execute_as_afl_target`](runtime/fn.execute_as_afl_target.html) for more. Requires the
+/// `afl` feature.
+///
+/// ## Examples
+///
+/// In a binary built with `cargo afl build`:
+///
+/// ```ignore
+/// use propfuzz::prelude::*;
+/// use proptest::collection::vec;
+///
+/// /// Test that reversing a list twice produces the same results.
+/// #[propfuzz]
+/// fn reverse(#[propfuzz(strategy = "vec(any::<u32>(), 0..64)")] mut list: Vec<u32>) {
+///     let list2 = list.clone();
+///     list.reverse();
+///     list.reverse();
+///     prop_assert_eq!(list, list2);
+/// }
+///
+/// propfuzz::afl_main!(__PROPFUZZ__reverse);
+/// ```
+#[cfg(feature = "afl")]
+#[macro_export]
+macro_rules! afl_main {
+    ($target:expr) => {
+        fn main() {
+            $crate::runtime::execute_as_afl_target($target);
+        }
+    };
+}
+
+/// Generates a `main` function that runs a propfuzz target under honggfuzz.
+///
+/// This works the same way as [`afl_main!`](macro.afl_main.html), except that binaries must be
+/// built with `cargo hfuzz build`. See
+/// [`runtime::execute_as_honggfuzz_target`](runtime/fn.execute_as_honggfuzz_target.html) for more.
+/// Requires the `honggfuzz` feature.
+#[cfg(feature = "honggfuzz")]
+#[macro_export]
+macro_rules! honggfuzz_main {
+    ($target:expr) => {
+        fn main() {
+            $crate::runtime::execute_as_honggfuzz_target($target);
+        }
+    };
+}
+
+// Not public API. Used by the code generated by the propfuzz macro for `runtime = "tokio"`, so that
+// a missing `tokio` feature is reported as such rather than as an unresolved path.
+#[cfg(feature = "tokio")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __tokio_block_on {
+    ($future:expr) => {
+        $crate::executor::tokio::block_on($future)
+    };
+}
+
+#[cfg(not(feature = "tokio"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __tokio_block_on {
+    ($future:expr) => {
+        compile_error!("runtime = \"tokio\" requires the propfuzz `tokio` feature")
+    };
+}
+
+// Not public API. Used by the code generated by the propfuzz macro.
+#[doc(hidden)]
+pub mod __private {
+    pub use linkme;
+
+    use proptest::test_runner::TestCaseError;
+    use std::fmt;
+    use std::future::Future;
+
+    /// Returns the given future unchanged, pinning down its output type so that `?` works within
+    /// async blocks.
+    pub fn with_output<T, F: Future<Output = T>>(future: F) -> F {
+        future
+    }
+
+    /// The error returned by a `#[propfuzz]` function that returns a `Result`.
+    ///
+    /// This uses autoref-based specialization, so that `TestCaseError`s (e.g. from `prop_assert!`)
+    /// are passed through as is, while other errors are turned into failures with their `Display`
+    /// output. Call as `(&ResultError(err)).to_test_case_error()`.
+    pub struct ResultError<E>(pub E);
+
+    pub trait ViaTestCaseError {
+        fn to_test_case_error(&self) -> TestCaseError;
+    }
+
+    impl ViaTestCaseError for ResultError<TestCaseError> {
+        fn to_test_case_error(&self) -> TestCaseError {
+            self.0.clone()
+        }
+    }
+
+    pub trait ViaDisplay {
+        fn to_test_case_error(&self) -> TestCaseError;
+    }
+
+    impl<E: fmt::Display> ViaDisplay for &ResultError<E> {
+        fn to_test_case_error(&self) -> TestCaseError {
+            TestCaseError::fail(self.0.to_string())
+        }
+    }
+}
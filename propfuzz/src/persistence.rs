@@ -0,0 +1,202 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Persistence of failing test cases.
+//!
+//! When a property-based test fails, the seed that generated the failing case is saved, and the
+//! next run replays saved seeds before generating new cases. This keeps failures found by a single
+//! run, such as a flaky CI job, from vanishing.
+//!
+//! `#[propfuzz]` targets pick where seeds are saved through the `persistence` argument:
+//! * `"with-source"` (the default): in a file next to the source file, with the extension
+//!   `.proptest-regressions`. See `proptest`'s `FileFailurePersistence::WithSource`.
+//! * `"source-parallel"`: in a `proptest-regressions` directory parallel to the crate's source
+//!   directory. See `proptest`'s `FileFailurePersistence::SourceParallel`.
+//! * `"off"`: failing seeds aren't saved.
+//! * any other value: in a file per target, within the given directory relative to the crate
+//!   root. See [`DirectoryPersistence`](struct.DirectoryPersistence.html).
+//!
+//! Saved seeds are meant to be checked into source control.
+
+use proptest::test_runner::{FailurePersistence, PersistedSeed};
+use std::any::Any;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Saves failing seeds for a single target in a file within a directory.
+///
+/// The file is named after the target, with `::` replaced by `__`, and uses the same format as
+/// `proptest`'s own persistence files.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DirectoryPersistence {
+    path: PathBuf,
+}
+
+impl DirectoryPersistence {
+    /// Creates a new `DirectoryPersistence` for the named target within `dir`.
+    pub fn new(dir: impl AsRef<Path>, name: &str) -> Self {
+        let file_name = format!("{}.txt", name.replace("::", "__"));
+        Self {
+            path: dir.as_ref().join(file_name),
+        }
+    }
+
+    /// Returns the path to the file that seeds are saved in.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn save(&self, seed: &PersistedSeed, shrunken_value: &dyn fmt::Debug) -> std::io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let is_new = !self.path.exists();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        if is_new {
+            writeln!(file, "{}", HEADER)?;
+        }
+        writeln!(file, "{} # shrinks to {:?}", seed, shrunken_value)
+    }
+}
+
+const HEADER: &str = "\
+# Seeds for failure cases propfuzz has generated in the past. It is
+# automatically read and these particular cases re-run before any
+# novel cases are generated.
+#
+# It is recommended to check this file in to source control so that
+# everyone who runs the test benefits from these saved cases.";
+
+impl FailurePersistence for DirectoryPersistence {
+    fn load_persisted_failures2(&self, _source_file: Option<&'static str>) -> Vec<PersistedSeed> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            // A missing file just means nothing has failed yet.
+            Err(_) => return vec![],
+        };
+        contents
+            .lines()
+            .filter_map(|line| {
+                // Each line contains a seed, optionally followed by a comment.
+                let seed = line.split('#').next()?.trim();
+                seed.parse().ok()
+            })
+            .collect()
+    }
+
+    fn save_persisted_failure2(
+        &mut self,
+        _source_file: Option<&'static str>,
+        seed: PersistedSeed,
+        shrunken_value: &dyn fmt::Debug,
+    ) {
+        if self.load_persisted_failures2(None).contains(&seed) {
+            return;
+        }
+        if let Err(err) = self.save(&seed, shrunken_value) {
+            eprintln!(
+                "propfuzz: failed to save failing seed to {}: {}",
+                self.path.display(),
+                err
+            );
+        }
+    }
+
+    fn box_clone(&self) -> Box<dyn FailurePersistence> {
+        Box::new(self.clone())
+    }
+
+    fn eq(&self, other: &dyn FailurePersistence) -> bool {
+        matches!(other.as_any().downcast_ref::<Self>(), Some(other) if self == other)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Holds on to the latest failing seed instead of saving it, and never loads any seeds.
+///
+/// Test runners configured with this generate new cases right away, and leave it to the caller to
+/// decide whether a failure is worth saving.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct DeferredPersistence {
+    latest: Arc<Mutex<Option<DeferredFailure>>>,
+}
+
+impl DeferredPersistence {
+    /// Returns the latest failing seed, if one was saved since the last call.
+    pub(crate) fn take(&self) -> Option<DeferredFailure> {
+        self.latest.lock().expect("lock poisoned").take()
+    }
+}
+
+impl FailurePersistence for DeferredPersistence {
+    fn load_persisted_failures2(&self, _source_file: Option<&'static str>) -> Vec<PersistedSeed> {
+        vec![]
+    }
+
+    fn save_persisted_failure2(
+        &mut self,
+        _source_file: Option<&'static str>,
+        seed: PersistedSeed,
+        shrunken_value: &dyn fmt::Debug,
+    ) {
+        *self.latest.lock().expect("lock poisoned") = Some(DeferredFailure {
+            seed,
+            shrunken_value: format!("{:?}", shrunken_value),
+        });
+    }
+
+    fn box_clone(&self) -> Box<dyn FailurePersistence> {
+        Box::new(self.clone())
+    }
+
+    fn eq(&self, other: &dyn FailurePersistence) -> bool {
+        matches!(
+            other.as_any().downcast_ref::<Self>(),
+            Some(other) if Arc::ptr_eq(&self.latest, &other.latest)
+        )
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// A failing seed held by `DeferredPersistence`.
+#[derive(Clone, Debug)]
+pub(crate) struct DeferredFailure {
+    seed: PersistedSeed,
+    shrunken_value: String,
+}
+
+impl DeferredFailure {
+    /// Saves this seed through `failure_persistence`.
+    pub(crate) fn save(
+        self,
+        failure_persistence: &mut dyn FailurePersistence,
+        source_file: Option<&'static str>,
+    ) {
+        failure_persistence.save_persisted_failure2(
+            source_file,
+            self.seed,
+            &Verbatim(self.shrunken_value),
+        );
+    }
+}
+
+/// Formats an already formatted value as is.
+struct Verbatim(String);
+
+impl fmt::Debug for Verbatim {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
@@ -4,7 +4,8 @@
 //! Re-exports for the most commonly used APIs of `propfuzz`.
 //!
 //! This includes:
-//! * the `propfuzz` macro from this crate
+//! * the `propfuzz` and `propfuzz_suite` macros from this crate
+//! * `ValueGenerator`, for writing corpus generators
 //! * the entire prelude of `proptest`, so existing tests can be migrated with minimal hassle.
 //!
 //! ## Examples
@@ -22,8 +23,10 @@
 //! }
 //! ```
 
+pub use crate::corpus::ValueGenerator;
+#[cfg(feature = "macro")]
 #[doc(no_inline)]
-pub use crate::propfuzz;
+pub use crate::{propfuzz, propfuzz_suite};
 
 #[doc(no_inline)]
 pub use proptest;
@@ -0,0 +1,181 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! A registry of all propfuzz targets linked into a binary.
+//!
+//! Every function annotated with `#[propfuzz]` registers itself here at link time, so fuzz
+//! drivers, listing tools and corpus generators can enumerate targets without maintaining lists
+//! by hand.
+//!
+//! ## Examples
+//!
+//! ```
+//! use propfuzz::prelude::*;
+//! use propfuzz::registry;
+//!
+//! /// Adding two numbers doesn't panic.
+//! #[propfuzz]
+//! fn add(a: u32, b: u32) {
+//!     let _ = a.wrapping_add(b);
+//! }
+//!
+//! # fn main() {
+//! for target in registry::all_targets() {
+//!     println!("{}: {}", target.name(), target.description().unwrap_or("(no description)"));
+//! }
+//! # }
+//! ```
+
+use crate::{runtime, traits::StructuredTarget};
+use linkme::distributed_slice;
+use proptest::test_runner::Config;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A type-erased view of a propfuzz target, as stored in the registry.
+///
+/// This is implemented for every [`StructuredTarget`](../traits/trait.StructuredTarget.html).
+pub trait RegisteredTarget: Send + Sync + fmt::Debug {
+    /// Returns the name of this target.
+    fn name(&self) -> &'static str;
+
+    /// Returns an optional description for this target.
+    fn description(&self) -> Option<&'static str>;
+
+    /// Returns true if this target should be fuzzed by default.
+    fn fuzz_default(&self) -> bool;
+
+    /// Returns the proptest config for this target.
+    fn proptest_config(&self) -> Config;
+
+    /// Executes this target as a standard property-based test.
+    ///
+    /// See [`runtime::execute_as_proptest`](../runtime/fn.execute_as_proptest.html).
+    fn execute_as_proptest(&self);
+
+    /// Executes this target once, using the given fuzzer input as the source of randomness.
+    ///
+    /// See [`runtime::execute_as_fuzz_target`](../runtime/fn.execute_as_fuzz_target.html).
+    fn execute_as_fuzz_target(&self, data: &[u8]);
+
+    /// Returns the proptest config used to run fuzzer input against this target.
+    ///
+    /// See [`runtime::fuzz_config`](../runtime/fn.fuzz_config.html).
+    fn fuzz_config(&self) -> Config;
+
+    /// Executes this target once, using a config returned by `fuzz_config`.
+    ///
+    /// See
+    /// [`runtime::execute_as_fuzz_target_with_config`](../runtime/fn.execute_as_fuzz_target_with_config.html).
+    fn execute_as_fuzz_target_with_config(&self, config: &Config, data: &[u8]);
+
+    /// Replays fuzzer input against this target, panicking with a readable report if it fails.
+    ///
+    /// See [`runtime::replay`](../runtime/fn.replay.html).
+    fn replay(&self, data: &[u8]);
+
+    /// Shrinks failing fuzzer input against this target, panicking with the minimal failing value.
+    ///
+    /// See [`runtime::shrink`](../runtime/fn.shrink.html).
+    fn shrink(&self, data: &[u8]);
+
+    /// Runs this target's examples that can't be turned into fuzzer input.
+    ///
+    /// See [`runtime::execute_examples`](../runtime/fn.execute_examples.html).
+    fn execute_examples(&self);
+
+    /// Returns the examples for this target as fuzzer input.
+    ///
+    /// See [`runtime::example_inputs`](../runtime/fn.example_inputs.html).
+    fn example_inputs(&self) -> Vec<Vec<u8>>;
+
+    /// Generates a seed corpus of `count` inputs for this target, and writes it out to `dir`.
+    ///
+    /// See [`runtime::generate_corpus`](../runtime/fn.generate_corpus.html).
+    fn generate_corpus(&self, dir: &Path, count: usize) -> io::Result<Vec<PathBuf>>;
+}
+
+impl<PF: StructuredTarget> RegisteredTarget for PF {
+    fn name(&self) -> &'static str {
+        StructuredTarget::name(self)
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        StructuredTarget::description(self)
+    }
+
+    fn fuzz_default(&self) -> bool {
+        StructuredTarget::fuzz_default(self)
+    }
+
+    fn proptest_config(&self) -> Config {
+        StructuredTarget::proptest_config(self)
+    }
+
+    fn execute_as_proptest(&self) {
+        runtime::execute_as_proptest(self)
+    }
+
+    fn execute_as_fuzz_target(&self, data: &[u8]) {
+        runtime::execute_as_fuzz_target(self, data)
+    }
+
+    fn fuzz_config(&self) -> Config {
+        runtime::fuzz_config(self)
+    }
+
+    fn execute_as_fuzz_target_with_config(&self, config: &Config, data: &[u8]) {
+        runtime::execute_as_fuzz_target_with_config(self, config, data)
+    }
+
+    fn replay(&self, data: &[u8]) {
+        runtime::replay(self, data)
+    }
+
+    fn shrink(&self, data: &[u8]) {
+        runtime::shrink(self, data)
+    }
+
+    fn execute_examples(&self) {
+        runtime::execute_examples(self)
+    }
+
+    fn example_inputs(&self) -> Vec<Vec<u8>> {
+        runtime::example_inputs(self)
+    }
+
+    fn generate_corpus(&self, dir: &Path, count: usize) -> io::Result<Vec<PathBuf>> {
+        runtime::generate_corpus(self, dir, count)
+    }
+}
+
+// Not public API. Populated by the code generated by the propfuzz macro.
+#[doc(hidden)]
+#[distributed_slice]
+pub static __PROPFUZZ_TARGETS: [&'static dyn RegisteredTarget] = [..];
+
+/// Returns all propfuzz targets linked into this binary, sorted by name.
+pub fn all_targets() -> Vec<&'static dyn RegisteredTarget> {
+    let mut targets: Vec<_> = __PROPFUZZ_TARGETS.iter().copied().collect();
+    targets.sort_by_key(|target| target.name());
+    targets
+}
+
+/// Returns the propfuzz targets linked into this binary that should be fuzzed by default, sorted
+/// by name.
+///
+/// These are the targets annotated with `#[propfuzz(fuzz_default = true)]`.
+pub fn fuzz_default_targets() -> Vec<&'static dyn RegisteredTarget> {
+    let mut targets = all_targets();
+    targets.retain(|target| target.fuzz_default());
+    targets
+}
+
+/// Returns the propfuzz target with the given name, if one is linked into this binary.
+pub fn find_target(name: &str) -> Option<&'static dyn RegisteredTarget> {
+    __PROPFUZZ_TARGETS
+        .iter()
+        .copied()
+        .find(|target| target.name() == name)
+}
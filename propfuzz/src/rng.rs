@@ -0,0 +1,65 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Random number generators backed by fuzzer input.
+//!
+//! Coverage-guided fuzzers produce byte sequences and mutate them based on the code paths they
+//! exercise. Structured fuzzing uses these byte sequences as the source of randomness for
+//! `proptest` strategies, so that the fuzzer is effectively mutating the values the strategies
+//! generate.
+
+use proptest::test_runner::{Config, RngAlgorithm, TestRng, TestRunner};
+
+/// A byte stream, such as fuzzer input, to be used as the source of randomness for `proptest`.
+///
+/// This isn't a random number generator itself. Instead, it creates `proptest` `TestRng`s and
+/// `TestRunner`s that read from the bytes, using `proptest`'s `RngAlgorithm::PassThrough`. This
+/// keeps the mapping from bytes to values in one place, and the RNGs created implement whichever
+/// version of `RngCore` `proptest` uses.
+///
+/// The RNGs created read bytes in order: `next_u32` reads the next 4 bytes as a little-endian
+/// integer, `next_u64` reads the next 8 bytes, and `fill_bytes` copies bytes out directly. Since
+/// each value is derived from a contiguous slice of the stream, mutating a byte in the input only
+/// changes the value generated from it. This locality is what keeps coverage-guided mutation
+/// effective.
+///
+/// Once the stream is exhausted, the RNGs deterministically return zeroes. `proptest` strategies
+/// tend to map zeroes to the simplest values (small numbers, empty collections), so short inputs
+/// produce small values.
+///
+/// # Examples
+///
+/// ```
+/// use propfuzz::rng::ByteStream;
+/// use proptest::prelude::*;
+/// use proptest::strategy::ValueTree;
+///
+/// let stream = ByteStream::new(&[0x2a, 0, 0, 0]);
+/// let mut test_runner = stream.into_test_runner(ProptestConfig::default());
+/// let value = any::<u32>().new_tree(&mut test_runner).unwrap().current();
+/// assert_eq!(value, 42);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ByteStream<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> ByteStream<'a> {
+    /// Creates a new `ByteStream` that reads from the start of the given bytes.
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    /// Creates a `proptest` `TestRng` that reads from the bytes.
+    pub fn into_test_rng(self) -> TestRng {
+        TestRng::from_seed(RngAlgorithm::PassThrough, self.data)
+    }
+
+    /// Creates a `TestRunner` with the given config that draws its randomness from the bytes.
+    ///
+    /// The config's RNG algorithm is set to `RngAlgorithm::PassThrough`.
+    pub fn into_test_runner(self, mut config: Config) -> TestRunner {
+        config.rng_algorithm = RngAlgorithm::PassThrough;
+        TestRunner::new_with_rng(config, self.into_test_rng())
+    }
+}
@@ -3,14 +3,120 @@
 
 //! Runtime support.
 
-use crate::traits::StructuredTarget;
-use proptest::test_runner::{TestError, TestRunner};
+use crate::corpus::{self, ValueGenerator};
+use crate::persistence::DeferredPersistence;
+use crate::{rng::ByteStream, traits::StructuredTarget};
+use proptest::prelude::RngCore;
+use proptest::strategy::{Strategy, ValueTree};
+use proptest::test_runner::{Config, RngAlgorithm, TestCaseError, TestError, TestRng, TestRunner};
+use std::any::Any;
+use std::env;
 use std::fmt;
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
 
-/// Executes a propfuzz target as a standard property-based test.
-pub fn execute_as_proptest(fuzz_target: impl StructuredTarget) {
+/// Returns the proptest config for a target, with overrides from the environment applied.
+fn target_config<PF: StructuredTarget>(fuzz_target: &PF) -> Config {
     let mut config = fuzz_target.proptest_config();
     config.test_name = Some(fuzz_target.name());
+    apply_env_overrides(fuzz_target.name(), &mut config);
+    config
+}
+
+/// Applies overrides from environment variables to the proptest config for the named target.
+///
+/// Every config field that can be set through `#[propfuzz]` can also be overridden through an
+/// environment variable named after it, e.g. `PROPFUZZ_CASES` or `PROPFUZZ_MAX_SHRINK_ITERS`. To
+/// only override a field for a single target, append two underscores and the target's name, with
+/// `::` replaced by `__` and other characters that can't be used in environment variables replaced
+/// by `_`, e.g. `PROPFUZZ_CASES__MY_CRATE__MY_TEST`. Per-target overrides take precedence.
+///
+/// The supported fields are `cases`, `max_local_rejects`, `max_global_rejects`,
+/// `max_flat_map_regens`, `fork`, `timeout`, `max_shrink_time`, `max_shrink_iters` and `verbose`.
+///
+/// This is applied to the config of every target run through this module.
+///
+/// # Panics
+///
+/// Panics if an environment variable is set to a value that can't be parsed.
+pub fn apply_env_overrides(name: &str, config: &mut Config) {
+    let suffix = env_suffix(name);
+
+    macro_rules! apply {
+        ($($field:ident: $var:expr,)*) => {
+            $(
+                if let Some(value) = read_override($var, &suffix) {
+                    config.$field = value;
+                }
+            )*
+        };
+    }
+
+    apply! {
+        cases: "PROPFUZZ_CASES",
+        max_local_rejects: "PROPFUZZ_MAX_LOCAL_REJECTS",
+        max_global_rejects: "PROPFUZZ_MAX_GLOBAL_REJECTS",
+        max_flat_map_regens: "PROPFUZZ_MAX_FLAT_MAP_REGENS",
+        fork: "PROPFUZZ_FORK",
+        timeout: "PROPFUZZ_TIMEOUT",
+        max_shrink_time: "PROPFUZZ_MAX_SHRINK_TIME",
+        max_shrink_iters: "PROPFUZZ_MAX_SHRINK_ITERS",
+        verbose: "PROPFUZZ_VERBOSE",
+    }
+}
+
+/// Turns a target name into a suffix for per-target environment variables.
+fn env_suffix(name: &str) -> String {
+    name.replace("::", "__")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn read_override<T: FromStr>(var: &str, suffix: &str) -> Option<T> {
+    let target_var = format!("{}__{}", var, suffix);
+    let (var, value) = match env::var(&target_var) {
+        Ok(value) => (target_var.as_str(), value),
+        Err(_) => (var, env::var(var).ok()?),
+    };
+    match value.parse() {
+        Ok(value) => Some(value),
+        Err(_) => panic!("invalid value for {}: '{}'", var, value),
+    }
+}
+
+/// The environment variable that turns property-based tests into time-boxed fuzz runs.
+///
+/// See [`execute_as_proptest`](fn.execute_as_proptest.html).
+pub const FUZZ_SECONDS_ENV: &str = "PROPFUZZ_FUZZ_SECONDS";
+
+/// Executes a propfuzz target as a standard property-based test.
+///
+/// If the `PROPFUZZ_FUZZ_SECONDS` environment variable is set to a number of seconds, the test
+/// instead keeps generating cases until that much time has passed, ignoring the configured number
+/// of cases. Every failure is shrunk, and at the end the number of cases run and each unique
+/// failure are reported. Failing seeds are saved and replayed the same way as for regular runs.
+/// This is meant for soaking tests for a fixed budget, e.g. in nightly CI, without a separate fuzz
+/// build.
+///
+/// In both cases, the target's [examples](../traits/trait.StructuredTarget.html#method.examples)
+/// are run first, and the test fails immediately if any of them fail.
+pub fn execute_as_proptest(fuzz_target: impl StructuredTarget) {
+    let config = target_config(&fuzz_target);
+    run_examples(&fuzz_target, |_| true);
+
+    if let Some(duration) = fuzz_duration() {
+        return execute_for_duration(fuzz_target, config, duration);
+    }
 
     let mut test_runner = TestRunner::new(config);
     match fuzz_target.execute(&mut test_runner) {
@@ -23,14 +129,541 @@ pub fn execute_as_proptest(fuzz_target: impl StructuredTarget) {
     }
 }
 
+/// Runs the test against each of the target's examples that can't be turned into fuzzer input,
+/// panicking on the first failure.
+///
+/// These are the examples left out by [`example_inputs`](fn.example_inputs.html), which covers
+/// every example for structured targets. Fuzzers that start off from `example_inputs` can call
+/// this beforehand, so that every example is run.
+pub fn execute_examples(fuzz_target: impl StructuredTarget) {
+    run_examples(&fuzz_target, |example| {
+        fuzz_target.value_to_bytes(example).is_none()
+    });
+}
+
+/// Runs the test against each of the target's examples for which `include` returns true, panicking
+/// on the first failure.
+fn run_examples<PF: StructuredTarget>(fuzz_target: &PF, include: impl Fn(&PF::Value) -> bool) {
+    for (index, example) in fuzz_target.examples().into_iter().enumerate() {
+        if !include(&example) {
+            continue;
+        }
+        let result = run_test(fuzz_target, example, || {
+            fuzz_target.examples().swap_remove(index)
+        });
+        if let Err(err) = result {
+            panic!("{}", TestErrorDisplay::new(fuzz_target, err).unshrunk());
+        }
+    }
+}
+
+fn fuzz_duration() -> Option<Duration> {
+    let value = env::var(FUZZ_SECONDS_ENV).ok()?;
+    match value.parse::<f64>() {
+        Ok(secs) if secs.is_finite() && secs >= 0.0 => Some(Duration::from_secs_f64(secs)),
+        _ => panic!(
+            "invalid value for {}: '{}' (expected a number of seconds)",
+            FUZZ_SECONDS_ENV, value
+        ),
+    }
+}
+
+/// Generates and runs cases until `duration` has passed, then reports every unique failure.
+///
+/// Cases are run through `StructuredTarget::execute`, so `proptest` takes care of failure
+/// persistence. Seeds saved through the config's failure persistence are replayed first, by a test
+/// runner that doesn't generate any new cases. As with regular runs, this stops at the first saved
+/// seed that fails. After that, each case is run by a test runner of its own, which holds on to
+/// the seed of a failing case so that only the seed of each new unique failure is saved.
+fn execute_for_duration<PF: StructuredTarget>(
+    fuzz_target: PF,
+    mut config: Config,
+    duration: Duration,
+) {
+    let start = Instant::now();
+    let source_file = config.source_file;
+    let mut failure_persistence = config.failure_persistence.take();
+    let mut cases: u64 = 0;
+    // Failures are deduplicated by their reason, and listed in the order they were found along with
+    // the first minimal failing input.
+    let mut failures: Vec<Failure> = vec![];
+
+    let mut replay_runner = TestRunner::new(Config {
+        cases: 0,
+        failure_persistence: failure_persistence.clone(),
+        ..config.clone()
+    });
+    if let Err(err) = fuzz_target.execute(&mut replay_runner) {
+        // Seeds that were replayed are already saved.
+        Failure::record(&mut failures, &fuzz_target, err);
+    }
+
+    let deferred = DeferredPersistence::default();
+    // The default limit on shrinking depends on the number of cases, so pin it first.
+    config.max_shrink_iters = config.max_shrink_iters();
+    config.cases = 1;
+    config.failure_persistence = Some(Box::new(deferred.clone()));
+    let mut seed_runner = TestRunner::new(config.clone());
+    while start.elapsed() < duration {
+        let mut test_runner = TestRunner::new_with_rng(config.clone(), seed_runner.new_rng());
+        cases += 1;
+        if let Err(err) = fuzz_target.execute(&mut test_runner) {
+            let failure = deferred.take();
+            let is_new = Failure::record(&mut failures, &fuzz_target, err);
+            if let (true, Some(failure_persistence), Some(failure)) =
+                (is_new, &mut failure_persistence, failure)
+            {
+                failure.save(&mut **failure_persistence, source_file);
+            }
+        }
+    }
+
+    let elapsed = start.elapsed();
+    eprintln!(
+        "propfuzz: {}: ran {} cases in {:.1}s ({:.0} cases/s), {} unique failures",
+        fuzz_target.name(),
+        cases,
+        elapsed.as_secs_f64(),
+        cases as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+        failures.len()
+    );
+
+    if !failures.is_empty() {
+        let mut message = format!(
+            "{} unique failures found in {} cases:",
+            failures.len(),
+            cases
+        );
+        for failure in &failures {
+            message.push_str(&format!(
+                "\n\n(seen {} times) {}",
+                failure.count, failure.report
+            ));
+        }
+        panic!("{}", message);
+    }
+}
+
+/// A unique failure found by `execute_for_duration`.
+struct Failure {
+    reason: String,
+    report: String,
+    count: u64,
+}
+
+impl Failure {
+    /// Adds a failure to the list, or bumps the count of an earlier one with the same reason.
+    /// Returns true if this is a new unique failure.
+    ///
+    /// Rejected cases aren't failures, so aborts are ignored.
+    fn record<PF: StructuredTarget>(
+        failures: &mut Vec<Failure>,
+        fuzz_target: &PF,
+        err: TestError<PF::Value>,
+    ) -> bool {
+        let reason = match &err {
+            TestError::Fail(why, _) => why.to_string(),
+            TestError::Abort(_) => return false,
+        };
+        match failures.iter_mut().find(|failure| failure.reason == reason) {
+            Some(failure) => {
+                failure.count += 1;
+                false
+            }
+            None => {
+                failures.push(Failure {
+                    reason,
+                    report: TestErrorDisplay::new(fuzz_target, err).to_string(),
+                    count: 1,
+                });
+                true
+            }
+        }
+    }
+}
+
+/// Executes a propfuzz target as a fuzz target, using the given input as the source of randomness.
+///
+/// The input bytes are used as a pass-through RNG (see
+/// [`ByteStream`](../rng/struct.ByteStream.html)): a single value is generated from them and
+/// the body of the test is run once against it. Unstructured targets, which take a `&[u8]` or
+/// `&str`, receive the input bytes directly instead. This is meant to be called from the entry
+/// point of a coverage-guided fuzzer, e.g. [libFuzzer](https://llvm.org/docs/LibFuzzer.html)
+/// through `libfuzzer_sys::fuzz_target!`.
+///
+/// Test failures, including panics within the body of the test, are reported by panicking so that
+/// the fuzzer records them as crashes. Inputs that can't be turned into a value, or that are
+/// rejected by the test, are ignored.
+///
+/// This looks up the target's config on every call. Fuzzers that run many inputs against the same
+/// target should get it once through [`fuzz_config`](fn.fuzz_config.html), and pass it to
+/// [`execute_as_fuzz_target_with_config`](fn.execute_as_fuzz_target_with_config.html) instead.
+pub fn execute_as_fuzz_target(fuzz_target: impl StructuredTarget, data: &[u8]) {
+    let config = fuzz_config(&fuzz_target);
+    execute_as_fuzz_target_with_config(fuzz_target, &config, data)
+}
+
+/// Executes a propfuzz target as a fuzz target, using a config returned by
+/// [`fuzz_config`](fn.fuzz_config.html).
+///
+/// See [`execute_as_fuzz_target`](fn.execute_as_fuzz_target.html) for more.
+pub fn execute_as_fuzz_target_with_config(
+    fuzz_target: impl StructuredTarget,
+    config: &Config,
+    data: &[u8],
+) {
+    match run_fuzz_input(&fuzz_target, config, data) {
+        Ok(()) | Err(TestError::Abort(_)) => (),
+        Err(err) => panic!("{}", TestErrorDisplay::new(&fuzz_target, err).unshrunk()),
+    }
+}
+
+/// Returns the proptest config used to run fuzzer input against a propfuzz target.
+///
+/// This is the target's config with overrides from the environment applied (see
+/// [`apply_env_overrides`](fn.apply_env_overrides.html)), except that shrinking and failure
+/// persistence are turned off, since fuzzers take care of minimizing and persisting failing inputs.
+pub fn fuzz_config(fuzz_target: impl StructuredTarget) -> Config {
+    let mut config = target_config(&fuzz_target);
+    config.max_shrink_iters = 0;
+    config.failure_persistence = None;
+    config
+}
+
+/// Replays fuzzer input against a propfuzz target, such as a crash found by a fuzzer.
+///
+/// The input is turned into a value the same way as in
+/// [`execute_as_fuzz_target`](fn.execute_as_fuzz_target.html), and the body of the test is run
+/// once against it.
+///
+/// # Panics
+///
+/// If the test fails, panics with the reason for the failure and the failing input, formatted the
+/// same way as property-based test failures. Unlike `execute_as_fuzz_target`, this also panics if
+/// the input can't be turned into a value.
+pub fn replay(fuzz_target: impl StructuredTarget, data: &[u8]) {
+    let config = fuzz_config(&fuzz_target);
+    if let Err(err) = run_fuzz_input(&fuzz_target, &config, data) {
+        panic!("{}", TestErrorDisplay::new(&fuzz_target, err).unshrunk());
+    }
+}
+
+/// Shrinks failing fuzzer input, such as a crash found by a fuzzer, to a minimal failing value.
+///
+/// The input is turned into a `proptest` value tree the same way as in
+/// [`execute_as_fuzz_target`](fn.execute_as_fuzz_target.html). If the test fails against its
+/// value, the value tree is shrunk with `proptest`'s standard shrinking loop, honoring
+/// `max_shrink_iters` and `max_shrink_time` from the target's config. Since strategies know the
+/// structure of the values they generate, this produces much more readable results than
+/// minimizing the raw input bytes.
+///
+/// Unstructured targets receive input bytes directly, so they have no value tree to shrink. Their
+/// input is replayed as is.
+///
+/// # Panics
+///
+/// If the test fails, panics with the reason for the failure and the minimal failing input. Also
+/// panics if the input can't be turned into a value.
+pub fn shrink(fuzz_target: impl StructuredTarget, data: &[u8]) {
+    if fuzz_target.is_unstructured() {
+        return replay(fuzz_target, data);
+    }
+
+    let mut config = target_config(&fuzz_target);
+    // The input is already persisted by the fuzzer.
+    config.failure_persistence = None;
+
+    let mut test_runner = ByteStream::new(data).into_test_runner(config);
+    let tree = match fuzz_target.strategy().new_tree(&mut test_runner) {
+        Ok(tree) => tree,
+        Err(why) => panic!(
+            "{}",
+            TestErrorDisplay::new(&fuzz_target, TestError::Abort(why))
+        ),
+    };
+
+    match test_runner.run_one(tree, |value| fuzz_target.test(value)) {
+        // Inputs rejected by the test aren't failures.
+        Ok(_) | Err(TestError::Abort(_)) => (),
+        Err(err) => panic!("{}", TestErrorDisplay::new(&fuzz_target, err)),
+    }
+}
+
+/// Executes a propfuzz target under [AFL++](https://aflplus.plus/), in persistent mode.
+///
+/// Each test case is read from the file named by the first command-line argument if one is
+/// passed in (as with `afl-fuzz ... -- binary @@`), or from standard input otherwise. It is then
+/// run through [`execute_as_fuzz_target`](fn.execute_as_fuzz_target.html). Test cases are run in a
+/// loop within the same process, which is much faster than forking for every input.
+///
+/// Test failures abort the process, after printing the failure, so that AFL++ records them as
+/// crashes.
+///
+/// This is usually called through [`afl_main!`](../macro.afl_main.html). Binaries that use it must
+/// be built with AFL++ instrumentation, e.g. through `cargo afl build`, which also links in the
+/// AFL++ runtime. Requires the `afl` feature.
+#[cfg(feature = "afl")]
+pub fn execute_as_afl_target(fuzz_target: impl StructuredTarget) {
+    use std::fs;
+    use std::io::Read;
+    use std::process;
+
+    // afl-fuzz looks for these signatures in the binary to enable persistent mode and the deferred
+    // fork server.
+    static PERSISTENT_SIG: &str = "##SIG_AFL_PERSISTENT##\0";
+    static DEFER_SIG: &str = "##SIG_AFL_DEFER_FORKSRV##\0";
+
+    extern "C" {
+        fn __afl_persistent_loop(max_count: u32) -> i32;
+        fn __afl_manual_init();
+    }
+
+    let config = fuzz_config(&fuzz_target);
+    let input_path = env::args_os().nth(1);
+    let mut data = vec![];
+
+    // Safety: the signatures are only read, and the AFL++ runtime functions take no pointers.
+    unsafe {
+        std::ptr::read_volatile(&PERSISTENT_SIG);
+        std::ptr::read_volatile(&DEFER_SIG);
+        __afl_manual_init();
+    }
+
+    // Restart the process every so often, in case of leaks.
+    while unsafe { __afl_persistent_loop(AFL_PERSISTENT_RUNS) } != 0 {
+        data.clear();
+        let res = match &input_path {
+            Some(path) => fs::File::open(path).and_then(|mut f| f.read_to_end(&mut data)),
+            None => io::stdin().read_to_end(&mut data),
+        };
+        if let Err(err) = res {
+            eprintln!("propfuzz: error reading test case: {}", err);
+            process::exit(2);
+        }
+
+        execute_or_abort(&fuzz_target, &config, &data);
+    }
+}
+
+#[cfg(feature = "afl")]
+const AFL_PERSISTENT_RUNS: u32 = 1000;
+
+/// Executes a propfuzz target under [honggfuzz](https://github.com/google/honggfuzz), in
+/// persistent mode.
+///
+/// Inputs are fetched from honggfuzz in a loop within the same process, and run through
+/// [`execute_as_fuzz_target`](fn.execute_as_fuzz_target.html). This function never returns.
+///
+/// Test failures abort the process, after printing the failure, so that honggfuzz records them as
+/// crashes.
+///
+/// This is usually called through [`honggfuzz_main!`](../macro.honggfuzz_main.html). Binaries that
+/// use it must be built with honggfuzz instrumentation, e.g. through `cargo hfuzz build`, which
+/// also links in the honggfuzz runtime. Requires the `honggfuzz` feature.
+#[cfg(feature = "honggfuzz")]
+pub fn execute_as_honggfuzz_target(fuzz_target: impl StructuredTarget) -> ! {
+    extern "C" {
+        fn HF_ITER(buf_ptr: *mut *const u8, len_ptr: *mut usize);
+    }
+
+    let config = fuzz_config(&fuzz_target);
+    loop {
+        let mut buf_ptr: *const u8 = std::ptr::null();
+        let mut len = 0;
+        // Safety: HF_ITER points buf_ptr at an input of length len, which stays valid until the
+        // next call.
+        let data = unsafe {
+            HF_ITER(&mut buf_ptr, &mut len);
+            std::slice::from_raw_parts(buf_ptr, len)
+        };
+        execute_or_abort(&fuzz_target, &config, data);
+    }
+}
+
+/// Runs a fuzzer input against a target, aborting the process if the test fails.
+///
+/// Fuzzers that run inputs in a loop within the same process detect crashes through signals, so
+/// panics must be turned into aborts.
+#[cfg(any(feature = "afl", feature = "honggfuzz"))]
+fn execute_or_abort<PF: StructuredTarget>(fuzz_target: &PF, config: &Config, data: &[u8]) {
+    // The panic message has already been printed by the panic hook.
+    let res = panic::catch_unwind(AssertUnwindSafe(|| {
+        execute_as_fuzz_target_with_config(fuzz_target, config, data)
+    }));
+    if res.is_err() {
+        std::process::abort();
+    }
+}
+
+/// Generates a seed corpus of `count` inputs for a propfuzz target, and writes it out to `dir`.
+///
+/// For targets with a
+/// [corpus generator](../traits/trait.StructuredTarget.html#method.corpus_generator), inputs are
+/// produced by it.
+///
+/// For structured targets, values are generated from the target's strategy, and the random bytes
+/// used to generate each value are recorded. Passing these bytes to
+/// [`execute_as_fuzz_target`](fn.execute_as_fuzz_target.html) reproduces the same value, so
+/// fuzzers can start off from inputs that already cover the shape of the strategy. (Strategies
+/// that create separate RNGs, such as `prop_flat_map`, aren't reproduced exactly.)
+///
+/// Values are generated using the target's proptest config. Each input is written to a file named
+/// after a hash of its contents, so duplicate inputs are only written once. Returns the paths of
+/// the files written, sorted and deduplicated.
+///
+/// The target's examples are also written out, if they can be turned back into fuzzer input (see
+/// [`example_inputs`](fn.example_inputs.html)). A warning is printed for examples that can't be.
+///
+/// Returns an error of kind `InvalidInput` for unstructured targets without a corpus generator.
+pub fn generate_corpus(
+    fuzz_target: impl StructuredTarget,
+    dir: &Path,
+    count: usize,
+) -> io::Result<Vec<PathBuf>> {
+    let config = target_config(&fuzz_target);
+
+    let mut paths = match fuzz_target.corpus_generator() {
+        Some(generate) => {
+            let mut generator = ValueGenerator::from_test_runner(TestRunner::new(config));
+            (0..count)
+                .map(|_| corpus::write_input(dir, "", &generate(&mut generator)))
+                .collect::<io::Result<Vec<_>>>()?
+        }
+        None if fuzz_target.is_unstructured() => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} does not have a corpus generator", fuzz_target.name()),
+            ));
+        }
+        None => {
+            let strategy = fuzz_target.strategy();
+            let mut seed_runner = TestRunner::new(config.clone());
+            let mut paths = Vec::with_capacity(count);
+            for _ in 0..count {
+                let mut seed = [0; 32];
+                seed_runner.rng().fill_bytes(&mut seed);
+                let rng = TestRng::from_seed(RngAlgorithm::Recorder, &seed);
+                let mut test_runner = TestRunner::new_with_rng(config.clone(), rng);
+                // Values the strategy fails to generate can't be used as seeds.
+                if strategy.new_tree(&mut test_runner).is_ok() {
+                    paths.push(corpus::write_input(dir, "", &test_runner.bytes_used())?);
+                }
+            }
+            paths
+        }
+    };
+    let inputs = example_inputs(&fuzz_target);
+    let skipped = fuzz_target.examples().len() - inputs.len();
+    if skipped > 0 {
+        eprintln!(
+            "propfuzz: {}: {} examples can't be turned into fuzzer input, and were left out of \
+             the corpus",
+            fuzz_target.name(),
+            skipped
+        );
+    }
+    for input in inputs {
+        paths.push(corpus::write_input(dir, "", &input)?);
+    }
+    paths.sort();
+    paths.dedup();
+    Ok(paths)
+}
+
+/// Returns the target's examples as fuzzer input, so that they can be added to corpora.
+///
+/// Only examples for unstructured targets can be turned back into fuzzer input, since the bytes
+/// behind a structured value can't be recovered from the value. For other targets, this returns
+/// an empty list, and examples can be run through
+/// [`execute_examples`](fn.execute_examples.html) instead.
+pub fn example_inputs(fuzz_target: impl StructuredTarget) -> Vec<Vec<u8>> {
+    fuzz_target
+        .examples()
+        .iter()
+        .filter_map(|example| fuzz_target.value_to_bytes(example))
+        .collect()
+}
+
+/// Runs the test once against the value for the given fuzzer input, without shrinking.
+fn run_fuzz_input<PF: StructuredTarget>(
+    fuzz_target: &PF,
+    config: &Config,
+    data: &[u8],
+) -> Result<(), TestError<PF::Value>> {
+    if fuzz_target.is_unstructured() {
+        let value = fuzz_target
+            .value_from_bytes(data)
+            .ok_or_else(|| TestError::Abort("input is not valid for this target".into()))?;
+        return run_test(fuzz_target, value, || {
+            fuzz_target
+                .value_from_bytes(data)
+                .expect("input was valid the first time")
+        });
+    }
+
+    let mut test_runner = ByteStream::new(data).into_test_runner(config.clone());
+    run_one_case(fuzz_target, &mut test_runner)
+}
+
+/// Generates a single value using the given test runner, and runs the test against it without
+/// shrinking.
+fn run_one_case<PF: StructuredTarget>(
+    fuzz_target: &PF,
+    test_runner: &mut TestRunner,
+) -> Result<(), TestError<PF::Value>> {
+    let tree = fuzz_target
+        .strategy()
+        .new_tree(test_runner)
+        .map_err(TestError::Abort)?;
+    run_test(fuzz_target, tree.current(), || tree.current())
+}
+
+/// Runs the test against the given value. If the test fails, `current` is called to produce the
+/// failing value again, since the test consumes it.
+///
+/// Panics within the body of the test are turned into failures.
+fn run_test<PF: StructuredTarget>(
+    fuzz_target: &PF,
+    value: PF::Value,
+    current: impl FnOnce() -> PF::Value,
+) -> Result<(), TestError<PF::Value>> {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| fuzz_target.test(value)));
+    match result {
+        Ok(Ok(())) | Ok(Err(TestCaseError::Reject(_))) => Ok(()),
+        Ok(Err(TestCaseError::Fail(why))) => Err(TestError::Fail(why, current())),
+        Err(payload) => Err(TestError::Fail(panic_message(&*payload).into(), current())),
+    }
+}
+
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "<unknown panic value>".to_string()
+    }
+}
+
 struct TestErrorDisplay<'a, PF, T> {
     fuzz_target: &'a PF,
     err: TestError<T>,
+    shrunk: bool,
 }
 
 impl<'a, PF, T> TestErrorDisplay<'a, PF, T> {
     fn new(fuzz_target: &'a PF, err: TestError<T>) -> Self {
-        Self { fuzz_target, err }
+        Self {
+            fuzz_target,
+            err,
+            shrunk: true,
+        }
+    }
+
+    /// Marks the failing input as not having been shrunk.
+    fn unshrunk(mut self) -> Self {
+        self.shrunk = false;
+        self
     }
 }
 
@@ -43,8 +676,13 @@ where
         match &self.err {
             TestError::Abort(why) => write!(f, "Test aborted: {}", why),
             TestError::Fail(why, what) => {
-                writeln!(f, "Test failed: {}\nminimal failing input:", why)?;
-                self.fuzz_target.fmt_value(&what, f)
+                let kind = if self.shrunk {
+                    "minimal failing"
+                } else {
+                    "failing"
+                };
+                writeln!(f, "Test failed: {}\n{} input:", why, kind)?;
+                self.fuzz_target.fmt_value(what, f)
             }
         }
     }
@@ -8,8 +8,10 @@
 //! * executing the test, given a test runner
 //! * formatting failing values
 
+use crate::corpus::ValueGenerator;
 use proptest::prelude::*;
-use proptest::test_runner::{TestError, TestRunner};
+use proptest::strategy::BoxedStrategy;
+use proptest::test_runner::{TestCaseResult, TestError, TestRunner};
 use std::fmt;
 
 /// Represents a structured fuzz target.
@@ -31,6 +33,16 @@ pub trait StructuredTarget: Send + Sync + fmt::Debug {
     /// Returns an optional description for this structured fuzz target.
     fn description(&self) -> Option<&'static str>;
 
+    /// Returns true if this target should be fuzzed by default.
+    ///
+    /// Fuzz drivers use this to pick the set of targets to fuzz when none are named explicitly.
+    ///
+    /// The default implementation returns false. The `#[propfuzz]` macro sets this through the
+    /// `fuzz_default` argument.
+    fn fuzz_default(&self) -> bool {
+        false
+    }
+
     /// Returns the proptest config for this fuzz target.
     ///
     /// The default implementation for the `#[propfuzz]` macro uses the default `proptest` config,
@@ -40,10 +52,71 @@ pub trait StructuredTarget: Send + Sync + fmt::Debug {
         ProptestConfig::default()
     }
 
-    /// Executes this test using the given test runner.
+    /// Returns the strategy used to generate values for this fuzz target.
+    ///
+    /// Property-based tests draw values from this strategy using a random number generator, while
+    /// fuzzers draw values from it using their input bytes.
+    fn strategy(&self) -> BoxedStrategy<Self::Value>;
+
+    /// Returns known inputs that this test should always be run against.
+    ///
+    /// Examples are run before any generated values, and are used to seed fuzzer corpora where
+    /// possible (see `value_to_bytes`). The `#[propfuzz]` macro returns the values specified
+    /// through `example` arguments. The default implementation returns no examples.
+    fn examples(&self) -> Vec<Self::Value> {
+        vec![]
+    }
+
+    /// Runs the body of this test against a single generated value.
     ///
     /// This is where the main body of the test goes.
-    fn execute(&self, test_runner: &mut TestRunner) -> Result<(), TestError<Self::Value>>;
+    fn test(&self, value: Self::Value) -> TestCaseResult;
+
+    /// Returns true if this is an unstructured target, which takes fuzzer input directly rather
+    /// than through `strategy`.
+    ///
+    /// The default implementation returns false. The `#[propfuzz]` macro returns true for functions
+    /// that take a single `&[u8]` or `&str` argument.
+    fn is_unstructured(&self) -> bool {
+        false
+    }
+
+    /// For unstructured targets, converts fuzzer input directly into a value.
+    ///
+    /// Returns `None` if the input isn't valid for this target, e.g. if it isn't valid UTF-8 for a
+    /// target that takes a `&str`. Such inputs are skipped.
+    ///
+    /// This is only called if `is_unstructured` returns true. The default implementation returns
+    /// `None`.
+    fn value_from_bytes(&self, _data: &[u8]) -> Option<Self::Value> {
+        None
+    }
+
+    /// For unstructured targets, converts a value back into fuzzer input.
+    ///
+    /// This is used to add examples to seed corpora. Structured values can't be converted back, so
+    /// the default implementation returns `None`. The `#[propfuzz]` macro implements this for
+    /// unstructured targets.
+    fn value_to_bytes(&self, _value: &Self::Value) -> Option<Vec<u8>> {
+        None
+    }
+
+    /// Returns a function that generates inputs for a seed corpus, if this target has one.
+    ///
+    /// The `#[propfuzz]` macro returns the function specified through the `corpus_generator`
+    /// argument. For unstructured targets without one, it returns a function that generates
+    /// inputs from `strategy`. The default implementation returns `None`.
+    fn corpus_generator(&self) -> Option<fn(&mut ValueGenerator) -> Vec<u8>> {
+        None
+    }
+
+    /// Executes this test using the given test runner.
+    ///
+    /// The default implementation runs `test` against values generated by `strategy`, shrinking
+    /// any failing values it finds.
+    fn execute(&self, test_runner: &mut TestRunner) -> Result<(), TestError<Self::Value>> {
+        test_runner.run(&self.strategy(), |value| self.test(value))
+    }
 
     /// Formats a failing test case for displaying to the user.
     ///
@@ -54,3 +127,59 @@ pub trait StructuredTarget: Send + Sync + fmt::Debug {
         write!(f, "{:?}", value)
     }
 }
+
+impl<PF: StructuredTarget + ?Sized> StructuredTarget for &PF {
+    type Value = PF::Value;
+
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        (**self).description()
+    }
+
+    fn fuzz_default(&self) -> bool {
+        (**self).fuzz_default()
+    }
+
+    fn proptest_config(&self) -> ProptestConfig {
+        (**self).proptest_config()
+    }
+
+    fn strategy(&self) -> BoxedStrategy<Self::Value> {
+        (**self).strategy()
+    }
+
+    fn examples(&self) -> Vec<Self::Value> {
+        (**self).examples()
+    }
+
+    fn test(&self, value: Self::Value) -> TestCaseResult {
+        (**self).test(value)
+    }
+
+    fn is_unstructured(&self) -> bool {
+        (**self).is_unstructured()
+    }
+
+    fn value_from_bytes(&self, data: &[u8]) -> Option<Self::Value> {
+        (**self).value_from_bytes(data)
+    }
+
+    fn value_to_bytes(&self, value: &Self::Value) -> Option<Vec<u8>> {
+        (**self).value_to_bytes(value)
+    }
+
+    fn corpus_generator(&self) -> Option<fn(&mut ValueGenerator) -> Vec<u8>> {
+        (**self).corpus_generator()
+    }
+
+    fn execute(&self, test_runner: &mut TestRunner) -> Result<(), TestError<Self::Value>> {
+        (**self).execute(test_runner)
+    }
+
+    fn fmt_value(&self, value: &Self::Value, f: &mut fmt::Formatter) -> fmt::Result {
+        (**self).fmt_value(value, f)
+    }
+}
@@ -0,0 +1,73 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Tests for `async fn` targets, using the built-in executor.
+
+use propfuzz::{executor::block_on, prelude::*, runtime::execute_as_proptest};
+use std::future::Future;
+use std::num::ParseIntError;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::thread;
+
+/// A future that is pending once, waking itself up from another thread.
+struct YieldOnce(bool);
+
+impl Future for YieldOnce {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        if self.0 {
+            return Poll::Ready(());
+        }
+        self.0 = true;
+        let waker = cx.waker().clone();
+        thread::spawn(move || waker.wake());
+        Poll::Pending
+    }
+}
+
+async fn double(x: u32) -> u32 {
+    YieldOnce(false).await;
+    x * 2
+}
+
+/// Async functions are run to completion for every case.
+#[propfuzz]
+async fn doubles(#[propfuzz(strategy = "0..1000u32")] x: u32) {
+    prop_assert_eq!(double(x).await, x + x);
+}
+
+/// Async functions can return errors.
+#[propfuzz(runtime = "builtin")]
+async fn parses(x: u32) -> Result<(), ParseIntError> {
+    YieldOnce(false).await;
+    assert_eq!(x.to_string().parse::<u32>()?, x);
+    Ok(())
+}
+
+/// Async unstructured targets are supported.
+#[propfuzz]
+async fn unstructured(data: &[u8]) {
+    YieldOnce(false).await;
+    prop_assert_eq!(data.to_vec(), data.to_vec());
+}
+
+/// This test fails for any non-zero input.
+#[propfuzz(persistence = "off")]
+#[ignore]
+async fn nonzero_fails(x: u8) {
+    YieldOnce(false).await;
+    prop_assert_eq!(x, 0);
+}
+
+#[test]
+fn block_on_yields() {
+    assert_eq!(block_on(double(21)), 42);
+}
+
+#[test]
+#[should_panic(expected = "minimal failing input:\nx = 1")]
+fn async_fails() {
+    execute_as_proptest(__PROPFUZZ__nonzero_fails);
+}
@@ -0,0 +1,22 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Tests for `async fn` targets run on `tokio`. Requires the `tokio` feature.
+
+use propfuzz::prelude::*;
+use std::num::ParseIntError;
+
+/// Tasks can be spawned onto the runtime.
+#[propfuzz(runtime = "tokio")]
+async fn spawn(x: u32) {
+    let handle = tokio::spawn(async move { x.wrapping_add(1) });
+    prop_assert_eq!(handle.await.expect("task completed"), x.wrapping_add(1));
+}
+
+/// Async functions on tokio can return errors.
+#[propfuzz(runtime = "tokio")]
+async fn parses(x: u8) -> Result<(), ParseIntError> {
+    tokio::task::yield_now().await;
+    assert_eq!(x.to_string().parse::<u8>()?, x);
+    Ok(())
+}
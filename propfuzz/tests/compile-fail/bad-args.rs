@@ -33,4 +33,56 @@ fn repeated_strategy(
 ) {
 }
 
+/// Corpus generators on structured targets.
+#[propfuzz(corpus_generator = "generate")]
+fn structured_corpus_generator(_: u8) {}
+
+/// Examples that aren't expression strings.
+#[propfuzz(example = 5)]
+fn example_not_string(_: u8) {}
+
+/// Return types other than Result.
+#[propfuzz]
+fn returns_u32(_: u8) -> u32 {
+    0
+}
+
+/// Types that are only named like a Result.
+struct ParseResult;
+
+#[propfuzz]
+fn returns_result_like(_: u8) -> ParseResult {
+    ParseResult
+}
+
+/// Runtimes on non-async functions, and unknown runtimes.
+#[propfuzz(runtime = "tokio")]
+fn sync_runtime(_: u8) {}
+
+#[propfuzz(runtime = "smol")]
+async fn unknown_runtime(_: u8) {}
+
+/// Generic functions without types, with unknown type parameters, and with lifetimes.
+#[propfuzz]
+fn missing_types<T: std::fmt::Debug>(_: T) {}
+
+#[propfuzz(types(T = "u8", U = "u8"))]
+fn unknown_param<T: std::fmt::Debug>(_: T) {}
+
+#[propfuzz(types(T = "u8"))]
+fn lifetime_param<'a, T: std::fmt::Debug>(_: T) {}
+
+#[propfuzz(types(T = ""))]
+fn empty_types<T: std::fmt::Debug>(_: T) {}
+
+/// Fixed arguments that don't exist, aren't arrays, or have attributes.
+#[propfuzz(params(y = "[1, 2]"))]
+fn unknown_fixed(_x: u8) {}
+
+#[propfuzz(params(x = "1"))]
+fn not_array(x: u8, _y: u8) {}
+
+#[propfuzz(params(x = "[1, 2]"))]
+fn fixed_strategy(#[propfuzz(strategy = "0..4u8")] x: u8, _y: u8) {}
+
 fn main() {}
@@ -0,0 +1,91 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Propfuzz suites that aren't supported.
+
+use propfuzz::prelude::*;
+
+/// Suites must be impls or modules.
+#[propfuzz_suite]
+fn not_impl(_: u8) {}
+
+#[derive(Default)]
+struct Foo;
+
+/// Trait impls aren't supported.
+#[propfuzz_suite]
+impl Clone for Foo {
+    fn clone(&self) -> Self {
+        Foo
+    }
+}
+
+#[derive(Default)]
+struct Bar<T>(T);
+
+/// Generic impls aren't supported.
+#[propfuzz_suite]
+impl<T: Default> Bar<T> {
+    #[propfuzz]
+    fn generic(&self, _: u8) {}
+}
+
+/// Unknown arguments.
+#[propfuzz_suite(fixtures = "Foo")]
+impl Foo {
+    #[propfuzz]
+    fn unknown(&self, _: u8) {}
+}
+
+/// Fixtures must be expressions.
+#[propfuzz_suite(fixture = 1)]
+impl Foo {
+    #[propfuzz]
+    fn bad_fixture(&self, _: u8) {}
+}
+
+/// Methods need arguments other than self.
+#[propfuzz_suite]
+impl Foo {
+    #[propfuzz]
+    fn no_args(&self) {}
+
+    #[propfuzz(cases = "many")]
+    fn bad_args(&self, _: u8) {}
+}
+
+/// Only modules can be instantiated with implementations.
+#[propfuzz_suite(for = "Foo")]
+impl Foo {
+    #[propfuzz]
+    fn impls(&self, _: u8) {}
+}
+
+/// Modules require implementations.
+#[propfuzz_suite]
+mod no_impls {}
+
+/// Modules don't have fixtures.
+#[propfuzz_suite(for = "Foo", fixture = "Foo")]
+mod fixture {}
+
+/// Implementations must be types.
+#[propfuzz_suite(for = "1")]
+mod not_types {}
+
+#[propfuzz_suite(for = "Foo, Bar<u8>")]
+mod bad_fns {
+    /// Functions need a type parameter for the implementation.
+    #[propfuzz]
+    fn not_generic(_: u8) {}
+
+    /// The implementation can't be instantiated through types.
+    #[propfuzz(types(S = "u8"))]
+    fn instantiated<S>(_: u8) {}
+
+    /// Methods aren't supported.
+    #[propfuzz]
+    fn method<S>(&self, _: u8) {}
+}
+
+fn main() {}
@@ -0,0 +1,56 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Propfuzz with bad strategy shorthands.
+
+use propfuzz::prelude::*;
+
+/// Ranges must be ranges.
+#[propfuzz]
+fn not_range(#[propfuzz(range = "5")] _: u8) {}
+
+/// Ranges must have a bound.
+#[propfuzz]
+fn unbounded(#[propfuzz(range = "..")] _: u8) {}
+
+/// Ranges require numeric types.
+#[propfuzz]
+fn range_type(#[propfuzz(range = "0..10")] _: String) {}
+
+/// Regexes must be valid.
+#[propfuzz]
+fn invalid_regex(#[propfuzz(regex = "[a-z")] _: String) {}
+
+/// Regexes require strings.
+#[propfuzz]
+fn regex_type(#[propfuzz(regex = "[a-z]+")] _: u8) {}
+
+/// Lengths must be lengths or ranges.
+#[propfuzz]
+fn not_len(#[propfuzz(len = "\"ten\"")] _: Vec<u8>) {}
+
+/// Lengths require collections.
+#[propfuzz]
+fn len_type(#[propfuzz(len = "0..10")] _: u64) {}
+
+/// Maps need key and value types.
+#[propfuzz]
+fn len_args(#[propfuzz(len = "0..10")] _: std::collections::HashMap) {}
+
+/// Filters must be paths.
+#[propfuzz]
+fn not_path(#[propfuzz(filter = "|x| x > 0")] _: u8) {}
+
+/// Only one strategy can be specified.
+#[propfuzz]
+fn conflicting(#[propfuzz(strategy = "any::<u8>()", range = "0..10")] _: u8) {}
+
+/// Keys can't be repeated.
+#[propfuzz]
+fn repeated(#[propfuzz(range = "0..10", range = "0..20", filter = "f", filter = "g")] _: u8) {}
+
+/// Values must be strings.
+#[propfuzz]
+fn not_string(#[propfuzz(range = 10, regex = 1, len = 2, filter = 3)] _: u8) {}
+
+fn main() {}
@@ -0,0 +1,12 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! The tokio runtime without the tokio feature.
+
+use propfuzz::prelude::*;
+
+/// Running on tokio requires the tokio feature.
+#[propfuzz(runtime = "tokio")]
+async fn tokio_runtime(_: u8) {}
+
+fn main() {}
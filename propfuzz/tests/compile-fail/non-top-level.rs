@@ -1,7 +1,7 @@
 // Copyright (c) The propfuzz Contributors
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-//! Propfuzz is not supported for methods, only for top-level functions.
+//! Propfuzz is not supported for methods outside of #[propfuzz_suite] impls.
 
 use propfuzz::propfuzz;
 
@@ -0,0 +1,16 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Propfuzz with unstructured arguments mixed with other arguments.
+
+use propfuzz::propfuzz;
+
+/// Unstructured arguments can't be combined with other arguments.
+#[propfuzz]
+fn mixed(data: &[u8], x: u32) {}
+
+/// Two unstructured arguments aren't allowed either.
+#[propfuzz]
+fn two_unstructured(a: &[u8], b: &str) {}
+
+fn main() {}
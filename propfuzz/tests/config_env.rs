@@ -0,0 +1,63 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Tests for overriding proptest configs through environment variables.
+//!
+//! Environment variables are process-wide, so these tests live in their own binary, and each test
+//! uses variables specific to its own target.
+
+use propfuzz::{prelude::*, runtime::apply_env_overrides, traits::StructuredTarget};
+use proptest::test_runner::Config;
+use std::env;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+#[test]
+fn global_override() {
+    env::set_var("PROPFUZZ_MAX_FLAT_MAP_REGENS", "17");
+    let mut config = Config::default();
+    apply_env_overrides("config_env::global", &mut config);
+    assert_eq!(config.max_flat_map_regens, 17);
+}
+
+#[test]
+fn target_override() {
+    env::set_var("PROPFUZZ_MAX_SHRINK_TIME", "100");
+    env::set_var("PROPFUZZ_MAX_SHRINK_TIME__CONFIG_ENV__TARGET", "200");
+    env::set_var("PROPFUZZ_FORK__CONFIG_ENV__TARGET", "true");
+    env::set_var("PROPFUZZ_TIMEOUT__CONFIG_ENV__TARGET", "5000");
+
+    let mut config = Config::default();
+    apply_env_overrides("config_env::target", &mut config);
+    assert_eq!(config.max_shrink_time, 200);
+    assert!(config.fork);
+    assert_eq!(config.timeout, 5000);
+
+    let mut config = Config::default();
+    apply_env_overrides("config_env::other", &mut config);
+    assert_eq!(config.max_shrink_time, 100);
+    assert!(!config.fork);
+}
+
+#[test]
+#[should_panic(expected = "invalid value for PROPFUZZ_VERBOSE__CONFIG_ENV__INVALID: 'loud'")]
+fn invalid_override() {
+    env::set_var("PROPFUZZ_VERBOSE__CONFIG_ENV__INVALID", "loud");
+    apply_env_overrides("config_env::invalid", &mut Config::default());
+}
+
+static RUNS: AtomicU32 = AtomicU32::new(0);
+
+/// Counts the number of cases run.
+#[propfuzz(cases = 1024)]
+#[ignore]
+fn count_cases(_x: u8) {
+    RUNS.fetch_add(1, Ordering::SeqCst);
+}
+
+#[test]
+fn cases_override() {
+    env::set_var("PROPFUZZ_CASES__CONFIG_ENV__COUNT_CASES", "5");
+    assert_eq!(__PROPFUZZ__count_cases.proptest_config().cases, 1024);
+    propfuzz::runtime::execute_as_proptest(__PROPFUZZ__count_cases);
+    assert_eq!(RUNS.load(Ordering::SeqCst), 5);
+}
@@ -0,0 +1,104 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Tests for propfuzz.toml configuration files.
+
+use propfuzz::{config::ConfigFile, prelude::*, traits::StructuredTarget};
+use proptest::test_runner::Config;
+use std::env;
+use std::fs;
+use std::io;
+
+static CONFIG: &str = r#"
+[default]
+cases = 100
+max_shrink_iters = 200
+
+[targets."my_crate::*"]
+cases = 300
+
+[targets."my_crate::parser::*"]
+cases = 400
+
+[profile.nightly]
+cases = 500
+verbose = 1
+
+[profile.nightly.targets."my_crate::parser::*"]
+cases = 600
+"#;
+
+fn apply(file: &ConfigFile, name: &str, profile: Option<&str>) -> Config {
+    let mut config = Config::default();
+    file.apply(name, profile, &mut config);
+    config
+}
+
+#[test]
+fn config_file_precedence() {
+    let file = ConfigFile::parse(CONFIG).expect("valid config");
+
+    let config = apply(&file, "other::test", None);
+    assert_eq!(config.cases, 100);
+    assert_eq!(config.max_shrink_iters, 200);
+    assert_eq!(apply(&file, "my_crate::test", None).cases, 300);
+    assert_eq!(apply(&file, "my_crate::parser::test", None).cases, 400);
+
+    let config = apply(&file, "other::test", Some("nightly"));
+    assert_eq!(config.cases, 500);
+    assert_eq!(config.verbose, 1);
+    assert_eq!(config.max_shrink_iters, 200);
+    assert_eq!(apply(&file, "my_crate::test", Some("nightly")).cases, 300);
+    assert_eq!(
+        apply(&file, "my_crate::parser::test", Some("nightly")).cases,
+        600
+    );
+
+    assert!(file.has_profile("nightly"));
+    assert!(!file.has_profile("ci"));
+    assert_eq!(apply(&file, "other::test", Some("ci")).cases, 100);
+}
+
+#[test]
+fn config_file_errors() {
+    for contents in &[
+        "[default]\ncases = -1",
+        "[default]\nnot_an_option = 1",
+        "[targets.\"[\"]\ncases = 1",
+        "[profile.ci]\nfork = 1",
+    ] {
+        let err = ConfigFile::parse(contents).expect_err("invalid config");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData, "for {}", contents);
+    }
+}
+
+/// The attribute takes precedence over the config file.
+#[propfuzz(max_shrink_iters = 7)]
+#[ignore]
+fn configured(_: u8) {}
+
+#[test]
+fn config_file_base_config() {
+    // The file is only read once per process, so this is the only test in this binary that reads
+    // it.
+    let dir = env::temp_dir().join("propfuzz-config-file-test");
+    let nested = dir.join("nested");
+    fs::create_dir_all(&nested).expect("directory created");
+    fs::write(
+        dir.join("propfuzz.toml"),
+        "[targets.\"config_file::*\"]\ncases = 42\nmax_shrink_iters = 42\n",
+    )
+    .expect("config written");
+    assert_eq!(
+        ConfigFile::find(&nested),
+        Some(dir.join("propfuzz.toml")),
+        "found config in parent directory"
+    );
+
+    env::set_var("CARGO_MANIFEST_DIR", &nested);
+    let config = __PROPFUZZ__configured.proptest_config();
+    assert_eq!(config.cases, 42);
+    assert_eq!(config.max_shrink_iters, 7);
+
+    fs::remove_dir_all(&dir).expect("directory removed");
+}
@@ -0,0 +1,101 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Tests for seed corpus generation.
+
+use propfuzz::{
+    prelude::*,
+    rng::ByteStream,
+    runtime::{execute_as_fuzz_target, generate_corpus},
+};
+use proptest::{collection::vec, strategy::ValueTree};
+use std::fs;
+use std::path::PathBuf;
+
+fn generate_u32(generator: &mut ValueGenerator) -> Vec<u8> {
+    generator.new_value(any::<u32>()).to_le_bytes().to_vec()
+}
+
+/// Decoding a u32 doesn't panic.
+#[propfuzz(corpus_generator = "generate_u32")]
+fn decode_u32(data: &[u8]) {
+    if data.len() == 4 {
+        let _ = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
+    }
+}
+
+/// Unstructured targets without a corpus generator use their strategy.
+#[propfuzz]
+fn parse_str(s: &str) {
+    let _ = s.parse::<u32>();
+}
+
+/// Structured targets record the bytes used to generate values.
+#[propfuzz]
+fn structured(#[propfuzz(strategy = "vec(any::<u32>(), 0..64)")] list: Vec<u32>) {
+    prop_assert!(list.len() < 64);
+}
+
+fn corpus_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("propfuzz-corpus-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn value_generator_deterministic() {
+    let mut generator1 = ValueGenerator::deterministic();
+    let mut generator2 = ValueGenerator::deterministic();
+    for _ in 0..16 {
+        assert_eq!(
+            generator1.new_value(any::<(u32, String)>()),
+            generator2.new_value(any::<(u32, String)>())
+        );
+    }
+}
+
+#[test]
+fn corpus_from_generator() {
+    let dir = corpus_dir("generator");
+    let paths = generate_corpus(__PROPFUZZ__decode_u32, &dir, 64).expect("corpus generated");
+    assert!(!paths.is_empty(), "at least one input written");
+    for path in &paths {
+        assert_eq!(fs::read(path).expect("input readable").len(), 4);
+    }
+    fs::remove_dir_all(dir).expect("corpus removed");
+}
+
+#[test]
+fn corpus_from_strategy() {
+    let dir = corpus_dir("strategy");
+    let paths = generate_corpus(__PROPFUZZ__parse_str, &dir, 64).expect("corpus generated");
+    assert!(!paths.is_empty(), "at least one input written");
+    for path in &paths {
+        let data = fs::read(path).expect("input readable");
+        assert!(String::from_utf8(data).is_ok(), "input is valid UTF-8");
+    }
+    fs::remove_dir_all(dir).expect("corpus removed");
+}
+
+#[test]
+fn corpus_structured() {
+    let dir = corpus_dir("structured");
+    let paths = generate_corpus(__PROPFUZZ__structured, &dir, 64).expect("corpus generated");
+    assert!(!paths.is_empty(), "at least one input written");
+
+    let mut max_len = 0;
+    for path in &paths {
+        let data = fs::read(path).expect("input readable");
+        execute_as_fuzz_target(__PROPFUZZ__structured, &data);
+
+        let mut test_runner = ByteStream::new(&data).into_test_runner(ProptestConfig::default());
+        let list = vec(any::<u32>(), 0..64)
+            .new_tree(&mut test_runner)
+            .expect("generating a value succeeded")
+            .current();
+        max_len = max_len.max(list.len());
+    }
+    // An empty input produces an empty list, while recorded inputs should cover longer lists.
+    assert!(max_len > 1, "corpus covers non-trivial lists");
+    fs::remove_dir_all(dir).expect("corpus removed");
+}
@@ -0,0 +1,64 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Tests for running targets through the driver test, the way `cargo propfuzz` does.
+
+use pretty_assertions::assert_eq;
+use propfuzz::{driver, prelude::*};
+use std::env;
+use std::process::{Command, Output};
+
+propfuzz::driver!();
+
+/// Adding zero doesn't change a number.
+#[propfuzz(fuzz_default = true)]
+fn add_zero(x: u64) {
+    prop_assert_eq!(x.wrapping_add(0), x);
+}
+
+/// This test always fails.
+#[propfuzz(persistence = "off")]
+#[ignore]
+fn always_fails(x: u8) {
+    prop_assert_eq!(x, x.wrapping_add(1), "failed with {}", x);
+}
+
+/// Runs the driver test in this binary with the given command.
+fn run_driver(command: &str, target: Option<&str>) -> Output {
+    let mut cmd = Command::new(env::current_exe().expect("current exe is available"));
+    cmd.arg(driver::TEST_NAME)
+        .arg("--exact")
+        .arg("--nocapture")
+        .arg("--quiet")
+        .env(driver::DRIVER_ENV, command);
+    if let Some(target) = target {
+        cmd.env(driver::TARGET_ENV, target);
+    }
+    cmd.output().expect("running the driver succeeded")
+}
+
+#[test]
+fn driver_list() {
+    let output = run_driver("list", None);
+    assert!(output.status.success(), "list succeeded");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<_> = stdout
+        .lines()
+        .filter(|line| line.starts_with(driver::LIST_PREFIX))
+        .collect();
+    assert_eq!(
+        lines,
+        vec![
+            "propfuzz-target\tdriver::add_zero\ttrue\tAdding zero doesn't change a number.",
+            "propfuzz-target\tdriver::always_fails\tfalse\tThis test always fails.",
+        ]
+    );
+}
+
+#[test]
+fn driver_test() {
+    let code = |target| run_driver("test", Some(target)).status.code();
+    assert_eq!(code("driver::add_zero"), Some(0));
+    assert_eq!(code("driver::always_fails"), Some(1));
+    assert_eq!(code("driver::missing"), Some(2));
+}
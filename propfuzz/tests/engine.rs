@@ -0,0 +1,73 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Tests for the in-process fuzz loop.
+
+use propfuzz::engine::{self, FuzzOptions};
+use propfuzz::prelude::*;
+use std::fs;
+use std::time::Duration;
+
+/// Sorting a list is idempotent.
+#[propfuzz]
+fn sort_idempotent(mut list: Vec<u8>) {
+    list.sort_unstable();
+    let list2 = list.clone();
+    list.sort_unstable();
+    prop_assert_eq!(list, list2);
+}
+
+/// This test fails for large enough inputs.
+#[propfuzz(persistence = "off")]
+#[ignore]
+fn small_numbers(x: u32) {
+    prop_assert!(x < 1 << 24);
+}
+
+#[test]
+fn fuzz_runs() {
+    let options = FuzzOptions {
+        runs: Some(256),
+        seed: 0,
+        ..FuzzOptions::default()
+    };
+    let stats = engine::fuzz(&__PROPFUZZ__sort_idempotent, &options)
+        .expect("no I/O errors")
+        .expect("no failures found");
+    assert_eq!(stats.runs, 256);
+}
+
+#[test]
+fn fuzz_max_total_time() {
+    let options = FuzzOptions {
+        max_total_time: Some(Duration::from_millis(100)),
+        ..FuzzOptions::default()
+    };
+    let stats = engine::fuzz(&__PROPFUZZ__sort_idempotent, &options)
+        .expect("no I/O errors")
+        .expect("no failures found");
+    assert!(stats.elapsed >= Duration::from_millis(100));
+}
+
+#[test]
+fn fuzz_finds_failure() {
+    let artifact_dir = std::env::temp_dir().join("propfuzz-engine-test");
+    let options = FuzzOptions {
+        runs: Some(1024),
+        seed: 0,
+        artifact_dir: Some(artifact_dir),
+        ..FuzzOptions::default()
+    };
+    let crash = engine::fuzz(&__PROPFUZZ__small_numbers, &options)
+        .expect("no I/O errors")
+        .expect_err("failure found");
+    assert!(
+        crash.message.contains("Test failed"),
+        "unexpected message: {}",
+        crash.message
+    );
+
+    let artifact = crash.artifact.expect("artifact written");
+    assert_eq!(fs::read(&artifact).expect("artifact readable"), crash.input);
+    fs::remove_file(artifact).expect("artifact removed");
+}
@@ -0,0 +1,115 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Tests for examples specified through `#[propfuzz(example = "...")]`.
+
+use propfuzz::{
+    engine::{self, FuzzOptions},
+    prelude::*,
+    runtime::{example_inputs, execute_as_proptest, execute_examples, generate_corpus},
+    traits::StructuredTarget,
+};
+use std::fs;
+
+/// Examples are parsed as expressions of the value tuple type.
+#[propfuzz(example = "(vec![0, 1],)", example = "(vec![],)")]
+fn sort_idempotent(mut list: Vec<u8>) {
+    list.sort_unstable();
+    let list2 = list.clone();
+    list.sort_unstable();
+    prop_assert_eq!(list, list2);
+}
+
+/// This test only fails for a value random generation is very unlikely to hit.
+#[propfuzz(example = "(1, 0xdead_beef)", persistence = "off")]
+#[ignore]
+fn magic_pair(x: u64, y: u64) {
+    prop_assert!(x != 1 || y != 0xdead_beef, "found the magic pair");
+}
+
+/// This test only fails for an input random generation is very unlikely to hit.
+#[propfuzz(example = r#""magic".to_string()"#, persistence = "off")]
+#[ignore]
+fn magic_str(s: &str) {
+    prop_assert_ne!(s, "magic");
+}
+
+#[test]
+fn examples_returned() {
+    assert_eq!(
+        __PROPFUZZ__sort_idempotent.examples(),
+        vec![(vec![0, 1],), (vec![],)]
+    );
+    assert_eq!(__PROPFUZZ__magic_str.examples(), vec!["magic".to_string()]);
+}
+
+#[test]
+#[should_panic(expected = "Test failed: found the magic pair")]
+fn examples_run_first() {
+    execute_as_proptest(__PROPFUZZ__magic_pair);
+}
+
+#[test]
+fn example_inputs_unstructured_only() {
+    assert_eq!(
+        example_inputs(__PROPFUZZ__magic_str),
+        vec![b"magic".to_vec()]
+    );
+    assert!(example_inputs(__PROPFUZZ__magic_pair).is_empty());
+}
+
+#[test]
+fn examples_in_corpus() {
+    let dir = std::env::temp_dir().join(format!("propfuzz-examples-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let paths = generate_corpus(__PROPFUZZ__magic_str, &dir, 4).expect("corpus generated");
+    let found = paths
+        .iter()
+        .any(|path| fs::read(path).expect("input readable") == b"magic");
+    fs::remove_dir_all(&dir).expect("corpus removed");
+    assert!(found, "example not written to corpus");
+}
+
+#[test]
+fn examples_fuzzed_first() {
+    let options = FuzzOptions {
+        runs: Some(1),
+        seed: 0,
+        ..FuzzOptions::default()
+    };
+    let crash = engine::fuzz(&__PROPFUZZ__magic_str, &options)
+        .expect("no I/O errors")
+        .expect_err("failure found");
+    assert_eq!(crash.input, b"magic");
+}
+
+#[test]
+fn structured_examples_fuzzed_first() {
+    let options = FuzzOptions {
+        runs: Some(1),
+        seed: 0,
+        ..FuzzOptions::default()
+    };
+    let crash = engine::fuzz(&__PROPFUZZ__magic_pair, &options)
+        .expect("no I/O errors")
+        .expect_err("failure found");
+    assert!(
+        crash.message.contains("found the magic pair"),
+        "unexpected message: {}",
+        crash.message
+    );
+    assert!(crash.input.is_empty());
+    assert_eq!(crash.stats.runs, 0);
+}
+
+#[test]
+fn execute_examples_structured_only() {
+    // Unstructured examples are run as fuzzer input instead.
+    execute_examples(__PROPFUZZ__magic_str);
+}
+
+#[test]
+#[should_panic(expected = "Test failed: found the magic pair")]
+fn execute_examples_structured() {
+    execute_examples(__PROPFUZZ__magic_pair);
+}
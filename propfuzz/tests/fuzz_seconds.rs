@@ -0,0 +1,139 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Tests for time-boxed fuzzing through PROPFUZZ_FUZZ_SECONDS.
+//!
+//! The environment variable is process-wide, so these tests live in their own binary.
+
+use propfuzz::{
+    persistence::DirectoryPersistence,
+    prelude::*,
+    runtime::{execute_as_proptest, FUZZ_SECONDS_ENV},
+};
+use proptest::test_runner::FailurePersistence;
+use std::any::Any;
+use std::env;
+use std::fs;
+use std::panic;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Sorting a list is idempotent.
+#[propfuzz]
+#[ignore]
+fn sort_idempotent(mut list: Vec<u8>) {
+    list.sort_unstable();
+    let list2 = list.clone();
+    list.sort_unstable();
+    prop_assert_eq!(list, list2);
+}
+
+/// This test fails for any non-zero input.
+#[propfuzz(persistence = "off")]
+#[ignore]
+fn nonzero_fails(x: u8) {
+    prop_assert_eq!(x, 0);
+}
+
+/// Same as nonzero_fails, except failing seeds are saved.
+#[propfuzz(persistence = "target/propfuzz-fuzz-seconds-test")]
+#[ignore]
+fn nonzero_saved(x: u8) {
+    prop_assert_eq!(x, 0);
+}
+
+/// This test only fails for a value random generation is very unlikely to hit.
+#[propfuzz(persistence = "target/propfuzz-fuzz-seconds-test")]
+#[ignore]
+fn magic_fails(x: u32) {
+    prop_assert_ne!(x, 0xabab_abab);
+}
+
+/// This test fails in two different ways.
+#[propfuzz(persistence = "off")]
+#[ignore]
+fn two_failures(x: u8, y: u8) {
+    prop_assert!(x < 200, "x is too large");
+    prop_assert!(y < 200, "y is too large");
+}
+
+#[test]
+fn fuzz_seconds_passes() {
+    env::set_var(FUZZ_SECONDS_ENV, "0.2");
+    let start = Instant::now();
+    execute_as_proptest(__PROPFUZZ__sort_idempotent);
+    assert!(start.elapsed() >= Duration::from_millis(200));
+}
+
+#[test]
+#[should_panic(expected = "1 unique failures found")]
+fn fuzz_seconds_fails() {
+    env::set_var(FUZZ_SECONDS_ENV, "0.2");
+    execute_as_proptest(__PROPFUZZ__nonzero_fails);
+}
+
+fn seed_file(name: &str) -> DirectoryPersistence {
+    DirectoryPersistence::new(
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("target/propfuzz-fuzz-seconds-test"),
+        name,
+    )
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(_) => panic!("expected a formatted panic message"),
+    }
+}
+
+#[test]
+fn fuzz_seconds_saves_seeds() {
+    let seeds = seed_file("fuzz_seconds::nonzero_saved");
+    let _ = fs::remove_file(seeds.path());
+
+    env::set_var(FUZZ_SECONDS_ENV, "0.2");
+    panic::catch_unwind(|| execute_as_proptest(__PROPFUZZ__nonzero_saved))
+        .expect_err("test should fail");
+
+    // Only the first failure with each reason is saved.
+    assert_eq!(
+        seeds.load_persisted_failures2(None).len(),
+        1,
+        "one seed saved"
+    );
+    let contents = fs::read_to_string(seeds.path()).expect("file written");
+    assert!(
+        contents.contains("# shrinks to (1,)"),
+        "unexpected contents: {}",
+        contents
+    );
+
+    fs::remove_file(seeds.path()).expect("file removed");
+}
+
+#[test]
+fn fuzz_seconds_replays_seeds() {
+    let seeds = seed_file("fuzz_seconds::magic_fails");
+    fs::create_dir_all(seeds.path().parent().expect("parent")).expect("directory created");
+    // A pass-through seed generates the failing value directly from its bytes.
+    fs::write(seeds.path(), "pt abababab\n").expect("file written");
+
+    env::set_var(FUZZ_SECONDS_ENV, "0.2");
+    let payload = panic::catch_unwind(|| execute_as_proptest(__PROPFUZZ__magic_fails))
+        .expect_err("test should fail");
+    let message = panic_message(payload);
+    assert!(
+        message.contains("1 unique failures found") && message.contains("2880154539"),
+        "unexpected message: {}",
+        message
+    );
+
+    fs::remove_file(seeds.path()).expect("file removed");
+}
+
+#[test]
+#[should_panic(expected = "2 unique failures found")]
+fn fuzz_seconds_dedupes() {
+    env::set_var(FUZZ_SECONDS_ENV, "0.5");
+    execute_as_proptest(__PROPFUZZ__two_failures);
+}
@@ -0,0 +1,134 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Tests for running propfuzz targets as fuzz targets.
+
+use propfuzz::{
+    prelude::*,
+    runtime::{
+        execute_as_fuzz_target, execute_as_fuzz_target_with_config, fuzz_config, replay, shrink,
+    },
+};
+use proptest::collection::vec;
+
+/// Test that reversing a list twice produces the same results.
+#[propfuzz]
+fn reverse(#[propfuzz(strategy = "vec(any::<u32>(), 0..64)")] mut list: Vec<u32>) {
+    let list2 = list.clone();
+    list.reverse();
+    list.reverse();
+    prop_assert_eq!(list, list2);
+}
+
+/// This test fails for any non-zero input.
+#[propfuzz(persistence = "off")]
+#[ignore]
+fn nonzero_fails(x: u8) {
+    prop_assert_eq!(x, 0);
+}
+
+/// This test panics for any non-zero input.
+#[propfuzz(persistence = "off")]
+#[ignore]
+fn nonzero_panics(x: u8) {
+    assert_eq!(x, 0);
+}
+
+/// Same as nonzero_fails, except failures aren't shrunk.
+#[propfuzz(max_shrink_iters = 0, persistence = "off")]
+#[ignore]
+fn nonzero_fails_no_shrink(x: u8) {
+    prop_assert_eq!(x, 0);
+}
+
+#[test]
+fn fuzz_target_passes() {
+    execute_as_fuzz_target(__PROPFUZZ__reverse, &[]);
+    execute_as_fuzz_target(
+        __PROPFUZZ__reverse,
+        &[0x10, 0, 0, 0, 1, 2, 3, 4, 5, 6, 7, 8],
+    );
+    execute_as_fuzz_target(__PROPFUZZ__reverse, &[0xff; 1024]);
+}
+
+#[test]
+fn fuzz_target_exhausted_input() {
+    // Once the input runs out, the RNG returns zeroes.
+    execute_as_fuzz_target(__PROPFUZZ__nonzero_fails, &[]);
+    execute_as_fuzz_target(__PROPFUZZ__nonzero_panics, &[]);
+}
+
+#[test]
+#[should_panic(expected = "failing input:\nx = 1")]
+fn fuzz_target_fails() {
+    execute_as_fuzz_target(__PROPFUZZ__nonzero_fails, &[1]);
+}
+
+#[test]
+#[should_panic(expected = "Test failed: assertion")]
+fn fuzz_target_panics() {
+    execute_as_fuzz_target(__PROPFUZZ__nonzero_panics, &[1]);
+}
+
+#[test]
+fn fuzz_config_passes() {
+    let config = fuzz_config(__PROPFUZZ__nonzero_fails);
+    assert_eq!(config.max_shrink_iters, 0);
+    assert!(config.failure_persistence.is_none());
+
+    execute_as_fuzz_target_with_config(__PROPFUZZ__nonzero_fails, &config, &[0]);
+    execute_as_fuzz_target_with_config(__PROPFUZZ__nonzero_fails, &config, &[]);
+}
+
+#[test]
+#[should_panic(expected = "failing input:\nx = 1")]
+fn fuzz_config_fails() {
+    let config = fuzz_config(__PROPFUZZ__nonzero_fails);
+    execute_as_fuzz_target_with_config(__PROPFUZZ__nonzero_fails, &config, &[0]);
+    execute_as_fuzz_target_with_config(__PROPFUZZ__nonzero_fails, &config, &[1]);
+}
+
+#[test]
+fn replay_passes() {
+    replay(
+        __PROPFUZZ__reverse,
+        &[0x10, 0, 0, 0, 1, 2, 3, 4, 5, 6, 7, 8],
+    );
+    replay(__PROPFUZZ__nonzero_fails, &[0]);
+}
+
+#[test]
+#[should_panic(expected = "Test failed: assertion failed: `(left == right)`")]
+fn replay_fails() {
+    replay(__PROPFUZZ__nonzero_fails, &[2]);
+}
+
+#[test]
+#[should_panic(expected = "failing input:\nx = 2")]
+fn replay_panics() {
+    replay(__PROPFUZZ__nonzero_panics, &[2]);
+}
+
+#[test]
+fn shrink_passes() {
+    shrink(__PROPFUZZ__reverse, &[0xff; 1024]);
+    shrink(__PROPFUZZ__nonzero_fails, &[0]);
+}
+
+#[test]
+#[should_panic(expected = "minimal failing input:\nx = 1")]
+fn shrink_fails() {
+    shrink(__PROPFUZZ__nonzero_fails, &[200]);
+}
+
+#[test]
+#[should_panic(expected = "minimal failing input:\nx = 1")]
+fn shrink_panics() {
+    shrink(__PROPFUZZ__nonzero_panics, &[200]);
+}
+
+#[test]
+#[should_panic(expected = "minimal failing input:\nx = 200")]
+fn shrink_max_shrink_iters() {
+    shrink(__PROPFUZZ__nonzero_fails_no_shrink, &[200]);
+}
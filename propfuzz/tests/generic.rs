@@ -0,0 +1,71 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Tests for generic functions instantiated through `#[propfuzz(types(...))]`.
+
+use propfuzz::{prelude::*, registry, runtime::execute_as_proptest, traits::StructuredTarget};
+use std::fmt::Debug;
+use std::str::FromStr;
+
+/// Formatting and parsing a value round-trips.
+#[propfuzz(types(T = "u8, u32, String"))]
+fn roundtrip<T>(value: T)
+where
+    T: Arbitrary + Debug + PartialEq + ToString + FromStr,
+    <T as FromStr>::Err: Debug,
+{
+    let parsed: T = value.to_string().parse().expect("value parses");
+    prop_assert_eq!(parsed, value);
+}
+
+/// Multiple type parameters are instantiated with every combination of types.
+#[propfuzz(types(A = "u8, i8", B = "Vec<u16>"))]
+fn pairs<A: Arbitrary + Debug + Clone, B: Arbitrary + Debug + Clone>(a: A, b: B) {
+    let pair = (a.clone(), b.clone());
+    prop_assert_eq!(format!("{:?}", pair), format!("({:?}, {:?})", a, b));
+}
+
+/// This test fails for any non-default value.
+#[propfuzz(types(T = "u8, bool"), persistence = "off")]
+#[ignore]
+fn default_only<T: Arbitrary + Debug + Default + PartialEq>(value: T) {
+    prop_assert_eq!(value, T::default());
+}
+
+#[test]
+fn generic_names() {
+    assert_eq!(__PROPFUZZ__roundtrip__u8.name(), "generic::roundtrip::<u8>");
+    assert_eq!(
+        __PROPFUZZ__roundtrip__String.name(),
+        "generic::roundtrip::<String>"
+    );
+    assert_eq!(
+        __PROPFUZZ__pairs__i8_Vec_u16.name(),
+        "generic::pairs::<i8, Vec<u16>>"
+    );
+}
+
+#[test]
+fn generic_registered() {
+    let names: Vec<_> = registry::all_targets()
+        .into_iter()
+        .map(|target| target.name())
+        .filter(|name| name.starts_with("generic::roundtrip") || name.starts_with("generic::pairs"))
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            "generic::pairs::<i8, Vec<u16>>",
+            "generic::pairs::<u8, Vec<u16>>",
+            "generic::roundtrip::<String>",
+            "generic::roundtrip::<u32>",
+            "generic::roundtrip::<u8>",
+        ]
+    );
+}
+
+#[test]
+#[should_panic(expected = "minimal failing input:\nvalue = true")]
+fn generic_fails() {
+    execute_as_proptest(__PROPFUZZ__default_only__bool);
+}
@@ -0,0 +1,82 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Tests for arguments fixed through `#[propfuzz(params(...))]`.
+
+use propfuzz::{prelude::*, registry, runtime::execute_as_proptest, traits::StructuredTarget};
+use std::fmt::Debug;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Mode {
+    Fast,
+    Safe,
+}
+
+fn checked_double(mode: Mode, x: u16) -> Option<u32> {
+    match mode {
+        Mode::Fast => Some(u32::from(x) * 2),
+        Mode::Safe => u32::from(x).checked_mul(2),
+    }
+}
+
+/// Fixed arguments are combined with generated ones.
+#[propfuzz(params(mode = "[Mode::Fast, Mode::Safe]"))]
+fn doubles(mode: Mode, x: u16) {
+    prop_assert_eq!(checked_double(mode, x), Some(u32::from(x) * 2));
+}
+
+/// Multiple fixed arguments are combined with every row of each other, and with types.
+#[propfuzz(
+    types(T = "u8, u16"),
+    params(shift = "[0, 4]", label = r#"["a", "b"]"#)
+)]
+fn shifts<T: Arbitrary + Debug + Into<u64>>(shift: u32, label: &str, value: T) {
+    let value: u64 = value.into();
+    prop_assert_eq!((value << shift) >> shift, value, "{}", label);
+}
+
+/// Every argument can be fixed.
+#[propfuzz(params(x = "[1, 2, 3]"))]
+fn all_fixed(x: u32) {
+    prop_assert!(x > 0);
+}
+
+/// This test only fails in safe mode.
+#[propfuzz(params(mode = "[Mode::Fast, Mode::Safe]"), persistence = "off")]
+#[ignore]
+fn safe_fails(mode: Mode, x: u8) {
+    prop_assert!(mode == Mode::Fast || x == 0, "failed in {:?} mode", mode);
+}
+
+#[test]
+fn params_names() {
+    assert_eq!(
+        __PROPFUZZ__doubles__mode_Fast.name(),
+        "params::doubles[mode=Fast]"
+    );
+    assert_eq!(
+        __PROPFUZZ__shifts__u16_shift_4_label_a.name(),
+        "params::shifts::<u16>[shift=4, label=\"a\"]"
+    );
+    assert_eq!(__PROPFUZZ__all_fixed__x_3.name(), "params::all_fixed[x=3]");
+}
+
+#[test]
+fn params_registered() {
+    let count = registry::all_targets()
+        .into_iter()
+        .filter(|target| target.name().starts_with("params::shifts"))
+        .count();
+    assert_eq!(count, 8);
+}
+
+#[test]
+fn params_fast_passes() {
+    execute_as_proptest(__PROPFUZZ__safe_fails__mode_Fast);
+}
+
+#[test]
+#[should_panic(expected = "failed in Safe mode")]
+fn params_safe_fails() {
+    execute_as_proptest(__PROPFUZZ__safe_fails__mode_Safe);
+}
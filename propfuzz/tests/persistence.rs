@@ -0,0 +1,123 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Tests for persisting failing seeds.
+
+use propfuzz::{persistence::DirectoryPersistence, prelude::*, traits::StructuredTarget};
+use proptest::test_runner::{FailurePersistence, FileFailurePersistence, TestRunner};
+use std::fs;
+use std::path::Path;
+
+/// Seeds are saved next to the source file by default.
+#[propfuzz]
+#[ignore]
+fn default_persistence(_: u8) {}
+
+/// This test fails for large enough inputs, and saves seeds next to the source file.
+#[propfuzz]
+#[ignore]
+fn default_failing(x: u32) {
+    prop_assert!(x < 1 << 24);
+}
+
+/// Seeds are saved in a parallel directory.
+#[propfuzz(persistence = "source-parallel")]
+#[ignore]
+fn source_parallel(_: u8) {}
+
+/// Seeds aren't saved.
+#[propfuzz(persistence = "off")]
+#[ignore]
+fn off(_: u8) {}
+
+/// This test fails for large enough inputs, and saves seeds in a custom directory.
+#[propfuzz(persistence = "target/propfuzz-persistence-test")]
+#[ignore]
+fn custom_directory(x: u32) {
+    prop_assert!(x < 1 << 24);
+}
+
+fn file_persistence(target: impl StructuredTarget) -> FileFailurePersistence {
+    let persistence = target
+        .proptest_config()
+        .failure_persistence
+        .expect("persistence enabled");
+    *persistence
+        .as_any()
+        .downcast_ref::<FileFailurePersistence>()
+        .expect("file persistence")
+}
+
+#[test]
+fn persistence_modes() {
+    assert_eq!(
+        file_persistence(__PROPFUZZ__default_persistence),
+        FileFailurePersistence::WithSource("proptest-regressions")
+    );
+    assert_eq!(
+        file_persistence(__PROPFUZZ__source_parallel),
+        FileFailurePersistence::SourceParallel("proptest-regressions")
+    );
+    assert!(__PROPFUZZ__off
+        .proptest_config()
+        .failure_persistence
+        .is_none());
+}
+
+#[test]
+fn persistence_default_writes() {
+    // file!() is relative to the workspace root, while tests run from the package directory.
+    let expected = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join(Path::new(file!()).file_name().expect("file name"))
+        .with_extension("proptest-regressions");
+    let _ = fs::remove_file(&expected);
+
+    __PROPFUZZ__default_failing
+        .execute(&mut TestRunner::new(
+            __PROPFUZZ__default_failing.proptest_config(),
+        ))
+        .expect_err("test should fail");
+    let contents = fs::read_to_string(&expected).expect("file written");
+    assert!(
+        contents.contains("# shrinks to (16777216,)"),
+        "unexpected contents: {}",
+        contents
+    );
+
+    fs::remove_file(&expected).expect("file removed");
+}
+
+#[test]
+fn persistence_custom_directory() {
+    let expected = DirectoryPersistence::new(
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("target/propfuzz-persistence-test"),
+        "persistence::custom_directory",
+    );
+    let _ = fs::remove_file(expected.path());
+
+    let config = __PROPFUZZ__custom_directory.proptest_config();
+    let persistence = config.failure_persistence.clone().expect("enabled");
+    assert_eq!(
+        persistence.as_any().downcast_ref::<DirectoryPersistence>(),
+        Some(&expected)
+    );
+
+    for _ in 0..2 {
+        __PROPFUZZ__custom_directory
+            .execute(&mut TestRunner::new(config.clone()))
+            .expect_err("test should fail");
+    }
+    // The failure found by the first run is replayed first by the second run, so only one seed
+    // is saved.
+    let seeds = expected.load_persisted_failures2(None);
+    assert_eq!(seeds.len(), 1, "one seed saved");
+    let contents = fs::read_to_string(expected.path()).expect("file written");
+    assert!(
+        contents.contains("# shrinks to (16777216,)"),
+        "unexpected contents: {}",
+        contents
+    );
+
+    fs::remove_file(expected.path()).expect("file removed");
+}
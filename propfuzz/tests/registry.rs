@@ -0,0 +1,80 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Tests for the target registry.
+
+use pretty_assertions::assert_eq;
+use propfuzz::{prelude::*, registry};
+
+/// Adding two numbers doesn't panic.
+#[propfuzz]
+fn add(a: u32, b: u32) {
+    let _ = a.wrapping_add(b);
+}
+
+#[propfuzz(cases = 16, fuzz_default = true)]
+fn multiply(a: u32, b: u32) {
+    let _ = a.wrapping_mul(b);
+}
+
+mod nested {
+    use super::*;
+
+    /// A target in a nested module.
+    #[propfuzz]
+    fn subtract(a: u32, b: u32) {
+        let _ = a.wrapping_sub(b);
+    }
+}
+
+#[test]
+fn all_targets() {
+    let names: Vec<_> = registry::all_targets()
+        .into_iter()
+        .map(|target| target.name())
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            "registry::add",
+            "registry::multiply",
+            "registry::nested::subtract"
+        ],
+        "targets are registered and sorted by name"
+    );
+}
+
+#[test]
+fn fuzz_default_targets() {
+    let names: Vec<_> = registry::fuzz_default_targets()
+        .into_iter()
+        .map(|target| target.name())
+        .collect();
+    assert_eq!(names, vec!["registry::multiply"]);
+}
+
+#[test]
+fn find_target() {
+    let add = registry::find_target("registry::add").expect("add is registered");
+    assert_eq!(add.description(), Some("Adding two numbers doesn't panic."));
+    assert_eq!(add.proptest_config().cases, 256);
+    assert!(!add.fuzz_default());
+
+    let multiply = registry::find_target("registry::multiply").expect("multiply is registered");
+    assert_eq!(multiply.description(), None);
+    assert_eq!(multiply.proptest_config().cases, 16);
+    assert!(multiply.fuzz_default());
+
+    let subtract =
+        registry::find_target("registry::nested::subtract").expect("subtract is registered");
+    assert_eq!(subtract.description(), Some("A target in a nested module."));
+
+    assert!(registry::find_target("registry::divide").is_none());
+}
+
+#[test]
+fn execute_registered() {
+    for target in registry::all_targets() {
+        target.execute_as_fuzz_target(&[1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+}
@@ -0,0 +1,62 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Tests for `#[propfuzz]` functions that return a `Result`.
+
+use propfuzz::{prelude::*, runtime::execute_as_proptest};
+use std::num::ParseIntError;
+
+/// Errors can be propagated with `?`.
+#[propfuzz]
+fn parse_roundtrip(x: u32) -> Result<(), ParseIntError> {
+    let parsed: u32 = x.to_string().parse()?;
+    assert_eq!(parsed, x);
+    Ok(())
+}
+
+/// Unstructured targets can return errors too.
+#[propfuzz]
+fn parse_str(s: &str) -> Result<(), String> {
+    if s.contains('\0') {
+        return Err("nul byte".to_string());
+    }
+    Ok(())
+}
+
+/// `TestCaseError` works with `prop_assert!` and `prop_assume!`.
+#[propfuzz]
+fn test_case_result(x: u8) -> Result<(), TestCaseError> {
+    prop_assume!(x < 128);
+    prop_assert!(x < 128);
+    Ok(())
+}
+
+/// This test returns an error for any non-zero input.
+#[propfuzz(persistence = "off")]
+#[ignore]
+fn nonzero_err(x: u8) -> Result<(), String> {
+    if x != 0 {
+        return Err(format!("nonzero value {}", x));
+    }
+    Ok(())
+}
+
+/// This test fails through `prop_assert!` for large enough inputs.
+#[propfuzz(persistence = "off")]
+#[ignore]
+fn large_fails(x: u8) -> Result<(), TestCaseError> {
+    prop_assert!(x < 200, "x is too large");
+    Ok(())
+}
+
+#[test]
+#[should_panic(expected = "Test failed: nonzero value 1\nminimal failing input:\nx = 1")]
+fn result_err_fails() {
+    execute_as_proptest(__PROPFUZZ__nonzero_err);
+}
+
+#[test]
+#[should_panic(expected = "Test failed: x is too large")]
+fn test_case_result_fails() {
+    execute_as_proptest(__PROPFUZZ__large_fails);
+}
@@ -0,0 +1,82 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Tests for RNGs backed by byte streams.
+
+use pretty_assertions::assert_eq;
+use propfuzz::{prelude::*, rng::ByteStream};
+use proptest::{
+    collection::vec,
+    strategy::ValueTree,
+    test_runner::{RngAlgorithm, TestRng, TestRunner},
+};
+
+#[test]
+fn reads_in_order() {
+    let data = [1, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 3, 4, 5];
+    let mut rng = ByteStream::new(&data).into_test_rng();
+    assert_eq!(rng.next_u32(), 1);
+    assert_eq!(rng.next_u64(), 2);
+
+    // Exhausted bytes are replaced with zeroes.
+    let mut buf = [0xff; 5];
+    rng.fill_bytes(&mut buf);
+    assert_eq!(buf, [3, 4, 5, 0, 0]);
+    assert_eq!(rng.next_u32(), 0);
+    assert_eq!(rng.next_u64(), 0);
+}
+
+#[test]
+fn mutations_are_local() {
+    let strategy = vec(any::<u32>(), 16);
+    let generate = |data: &[u8]| {
+        let mut test_runner = ByteStream::new(data).into_test_runner(ProptestConfig::default());
+        strategy
+            .new_tree(&mut test_runner)
+            .expect("generating a value succeeded")
+            .current()
+    };
+
+    let data: Vec<u8> = (0..128).collect();
+    let original = generate(&data);
+
+    // Flipping a byte should change at most one element of the generated list.
+    for idx in 0..data.len() {
+        let mut mutated = data.clone();
+        mutated[idx] ^= 0x55;
+        let changed = original
+            .iter()
+            .zip(&generate(&mutated))
+            .filter(|(a, b)| a != b)
+            .count();
+        assert!(changed <= 1, "byte {} changed {} elements", idx, changed);
+    }
+}
+
+#[test]
+fn recorded_bytes_replay() {
+    let strategy = (
+        vec(any::<u32>(), 0..64),
+        any::<String>(),
+        any::<Option<u8>>(),
+    );
+    for seed in 0..16u8 {
+        let rng = TestRng::from_seed(RngAlgorithm::Recorder, &[seed; 32]);
+        let mut recorder = TestRunner::new_with_rng(ProptestConfig::default(), rng);
+        let recorded = strategy
+            .new_tree(&mut recorder)
+            .expect("generating a value succeeded")
+            .current();
+
+        let bytes = recorder.bytes_used();
+        let mut replayer = ByteStream::new(&bytes).into_test_runner(ProptestConfig::default());
+        let replayed = strategy
+            .new_tree(&mut replayer)
+            .expect("generating a value succeeded")
+            .current();
+        assert_eq!(
+            recorded, replayed,
+            "replaying recorded bytes produces the same value"
+        );
+    }
+}
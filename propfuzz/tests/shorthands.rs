@@ -0,0 +1,66 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Tests for the `range`, `regex`, `len` and `filter` shorthands for strategies.
+
+use propfuzz::prelude::*;
+use std::collections::{BTreeMap, HashSet, VecDeque};
+
+fn is_even(x: &u32) -> bool {
+    x & 1 == 0
+}
+
+fn is_ascii(s: &str) -> bool {
+    s.is_ascii()
+}
+
+/// Numbers are generated within the range.
+#[propfuzz]
+fn ranges(
+    #[propfuzz(range = "10..20")] x: u32,
+    #[propfuzz(range = "-5..=5")] y: i64,
+    #[propfuzz(range = "0.0..1.0")] z: f64,
+) {
+    prop_assert!((10..20).contains(&x));
+    prop_assert!((-5..=5).contains(&y));
+    prop_assert!((0.0..1.0).contains(&z));
+}
+
+/// Strings match the regex.
+#[propfuzz]
+fn regexes(#[propfuzz(regex = "[a-z]{1,8}")] s: String) {
+    prop_assert!(!s.is_empty() && s.len() <= 8);
+    prop_assert!(s.chars().all(|c| c.is_ascii_lowercase()));
+}
+
+/// Collections have a length within the range.
+#[propfuzz]
+fn lens(
+    #[propfuzz(len = "0..4")] list: Vec<u8>,
+    #[propfuzz(len = "2")] deque: VecDeque<bool>,
+    #[propfuzz(len = "1..=3")] set: HashSet<u32>,
+    #[propfuzz(len = "0..8")] map: BTreeMap<u16, String>,
+) {
+    prop_assert!(list.len() < 4);
+    prop_assert_eq!(deque.len(), 2);
+    prop_assert!((1..=3).contains(&set.len()));
+    prop_assert!(map.len() < 8);
+}
+
+/// Filters apply to the canonical strategy, or to the strategy from another key.
+#[propfuzz]
+fn filters(
+    #[propfuzz(filter = "is_even")] x: u32,
+    #[propfuzz(range = "0..100", filter = "is_even")] y: u32,
+    #[propfuzz(strategy = "\"[a-z]*\"", filter = "is_ascii")] s: String,
+) {
+    prop_assert_eq!(x % 2, 0);
+    prop_assert!(y < 100 && y % 2 == 0);
+    prop_assert!(s.is_ascii());
+}
+
+/// Shorthands work for unstructured targets too.
+#[propfuzz]
+fn unstructured(#[propfuzz(len = "0..16")] data: &[u8]) {
+    prop_assert!(data.len() < 16);
+}
@@ -0,0 +1,171 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Tests for `#[propfuzz]` methods in `#[propfuzz_suite]` impls.
+
+use propfuzz::{prelude::*, registry, runtime::execute_as_proptest, traits::StructuredTarget};
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A table with a prepared schema.
+struct Table {
+    columns: Vec<&'static str>,
+    rows: BTreeMap<u32, Vec<u8>>,
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Self::with_columns(&["id"])
+    }
+}
+
+impl Table {
+    fn with_columns(columns: &[&'static str]) -> Self {
+        Self {
+            columns: columns.to_vec(),
+            rows: BTreeMap::new(),
+        }
+    }
+}
+
+#[propfuzz_suite]
+impl Table {
+    /// Inserting a row makes it available.
+    #[propfuzz]
+    fn insert_get(&mut self, key: u32, value: Vec<u8>) {
+        self.rows.insert(key, value.clone());
+        prop_assert_eq!(self.rows.get(&key), Some(&value));
+    }
+
+    /// Each test case starts out with an empty table.
+    #[propfuzz(cases = 16)]
+    fn starts_empty(&mut self, key: u32) {
+        prop_assert!(self.rows.is_empty());
+        self.rows.insert(key, vec![]);
+    }
+
+    /// Fixtures can be consumed.
+    #[propfuzz]
+    fn into_columns(self, _: u8) {
+        prop_assert_eq!(self.columns, vec!["id"]);
+    }
+
+    /// Associated functions don't use a fixture.
+    #[propfuzz]
+    fn no_fixture(x: u8) {
+        prop_assert_eq!(x.wrapping_add(0), x);
+    }
+
+    /// Unstructured inputs are supported.
+    #[propfuzz]
+    fn unstructured(&self, data: &[u8]) {
+        prop_assert!(!self.rows.contains_key(&(data.len() as u32)));
+    }
+
+    /// Fixed and generic arguments are supported.
+    #[propfuzz(types(T = "u8, u16"), params(shift = "[0, 4]"))]
+    fn shifts<T: Arbitrary + Debug + Into<u64>>(&self, shift: u32, value: T) {
+        let value: u64 = value.into();
+        prop_assert_eq!((value << shift) >> shift, value);
+    }
+
+    /// Methods can return errors.
+    #[propfuzz]
+    fn parses(&self, x: u32) -> Result<(), std::num::ParseIntError> {
+        assert_eq!(x.to_string().parse::<u32>()?, x);
+        assert_eq!(self.columns.len().to_string().parse::<usize>()?, 1);
+        Ok(())
+    }
+
+    /// Async methods are supported.
+    #[propfuzz]
+    async fn async_insert(&mut self, key: u32) {
+        self.rows.insert(key, vec![]);
+        prop_assert!(self.rows.contains_key(&key));
+    }
+
+    /// This test always fails.
+    #[propfuzz(persistence = "off")]
+    #[ignore]
+    fn always_fails(&self, x: u8) {
+        prop_assert!(self.columns.is_empty(), "failed with {}", x);
+    }
+
+    /// Other items are left alone.
+    fn column_count(&self) -> usize {
+        self.columns.len()
+    }
+}
+
+static FIXTURES_BUILT: AtomicUsize = AtomicUsize::new(0);
+
+struct Schema {
+    columns: usize,
+}
+
+impl Schema {
+    fn prepare() -> Self {
+        FIXTURES_BUILT.fetch_add(1, Ordering::SeqCst);
+        Self { columns: 3 }
+    }
+}
+
+#[propfuzz_suite(fixture = "Self::prepare()")]
+impl Schema {
+    /// The fixture is built through the given expression.
+    #[propfuzz(cases = 10)]
+    fn prepared(&self, _: u8) {
+        prop_assert_eq!(self.columns, 3);
+    }
+}
+
+#[test]
+fn suite_names() {
+    assert_eq!(
+        __PROPFUZZ__Table__insert_get.name(),
+        "suite::Table::insert_get"
+    );
+    assert_eq!(
+        __PROPFUZZ__Table__insert_get.description(),
+        Some("Inserting a row makes it available.")
+    );
+    assert_eq!(
+        __PROPFUZZ__Table__shifts__u16_shift_4.name(),
+        "suite::Table::shifts::<u16>[shift=4]"
+    );
+    assert_eq!(
+        __PROPFUZZ__Schema__prepared.name(),
+        "suite::Schema::prepared"
+    );
+}
+
+#[test]
+fn suite_registered() {
+    let count = registry::all_targets()
+        .into_iter()
+        .filter(|target| target.name().starts_with("suite::Table::"))
+        .count();
+    assert_eq!(count, 12);
+}
+
+#[test]
+fn suite_fixture_per_case() {
+    let before = FIXTURES_BUILT.load(Ordering::SeqCst);
+    execute_as_proptest(__PROPFUZZ__Schema__prepared);
+    assert!(FIXTURES_BUILT.load(Ordering::SeqCst) - before >= 10);
+}
+
+#[test]
+fn suite_methods_callable() {
+    let mut table = Table::default();
+    assert_eq!(table.column_count(), 1);
+    assert!(table.insert_get(1, vec![2]).is_ok());
+    assert!(table.always_fails(0).is_err());
+}
+
+#[test]
+#[should_panic(expected = "failed with 0")]
+fn suite_failing() {
+    execute_as_proptest(__PROPFUZZ__Table__always_fails);
+}
@@ -0,0 +1,156 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Tests for `#[propfuzz_suite(for = "...")]` modules.
+
+use propfuzz::{prelude::*, registry, runtime::execute_as_proptest, traits::StructuredTarget};
+use std::collections::BTreeMap;
+
+/// The contract that every store must uphold.
+trait KvStore: Default {
+    fn put(&mut self, key: u8, value: u32);
+    fn get(&self, key: u8) -> Option<u32>;
+}
+
+#[derive(Default)]
+struct MemStore(BTreeMap<u8, u32>);
+
+impl KvStore for MemStore {
+    fn put(&mut self, key: u8, value: u32) {
+        self.0.insert(key, value);
+    }
+
+    fn get(&self, key: u8) -> Option<u32> {
+        self.0.get(&key).copied()
+    }
+}
+
+#[derive(Default)]
+struct VecStore(Vec<(u8, u32)>);
+
+impl KvStore for VecStore {
+    fn put(&mut self, key: u8, value: u32) {
+        self.0.retain(|(k, _)| *k != key);
+        self.0.push((key, value));
+    }
+
+    fn get(&self, key: u8) -> Option<u32> {
+        self.0.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+    }
+}
+
+/// A store that forgets everything.
+#[derive(Default)]
+struct NullStore;
+
+impl KvStore for NullStore {
+    fn put(&mut self, _: u8, _: u32) {}
+
+    fn get(&self, _: u8) -> Option<u32> {
+        None
+    }
+}
+
+#[propfuzz_suite(for = "MemStore, VecStore")]
+mod kv {
+    use super::*;
+
+    /// Values can be read back after they're put.
+    #[propfuzz]
+    fn get_after_put<S: KvStore>(key: u8, value: u32) {
+        let mut store = S::default();
+        store.put(key, value);
+        prop_assert_eq!(store.get(key), Some(value));
+    }
+
+    /// Later values replace earlier ones.
+    #[propfuzz(cases = 64)]
+    fn overwrite<S: KvStore>(key: u8, first: u32, second: u32) {
+        let mut store = S::default();
+        store.put(key, first);
+        store.put(key, second);
+        prop_assert_eq!(store.get(key), Some(second));
+    }
+
+    /// Suites can be combined with types and params.
+    #[propfuzz(types(T = "u8, u16"), params(count = "[1, 4]"))]
+    fn puts_many<S: KvStore, T: Arbitrary + Into<u32>>(count: u8, value: T) {
+        let value = value.into();
+        let mut store = S::default();
+        for key in 0..count {
+            store.put(key, value);
+        }
+        prop_assert_eq!(store.get(count - 1), Some(value));
+    }
+
+    /// Other items are left alone.
+    pub(super) fn new_store<S: KvStore>() -> S {
+        S::default()
+    }
+
+    #[test]
+    fn suite_module_names() {
+        assert_eq!(
+            __PROPFUZZ__get_after_put__MemStore.name(),
+            "suite_module::kv::MemStore::get_after_put"
+        );
+        assert_eq!(
+            __PROPFUZZ__get_after_put__MemStore.description(),
+            Some("Values can be read back after they're put.")
+        );
+        assert_eq!(
+            __PROPFUZZ__overwrite__VecStore.name(),
+            "suite_module::kv::VecStore::overwrite"
+        );
+        assert_eq!(
+            __PROPFUZZ__puts_many__VecStore_u16_count_4.name(),
+            "suite_module::kv::VecStore::puts_many::<u16>[count=4]"
+        );
+    }
+}
+
+#[propfuzz_suite(for = "NullStore")]
+mod broken {
+    use super::*;
+
+    /// This test always fails.
+    #[propfuzz(persistence = "off")]
+    #[ignore]
+    fn get_after_put<S: KvStore>(key: u8, value: u32) {
+        let mut store = S::default();
+        store.put(key, value);
+        prop_assert_eq!(store.get(key), Some(value), "lost {}", key);
+    }
+
+    #[test]
+    #[should_panic(expected = "lost")]
+    fn suite_module_failing() {
+        execute_as_proptest(__PROPFUZZ__get_after_put__NullStore);
+    }
+}
+
+#[test]
+fn suite_module_registered() {
+    let names: Vec<_> = registry::all_targets()
+        .into_iter()
+        .map(|target| target.name())
+        .filter(|name| name.starts_with("suite_module::kv::"))
+        .collect();
+    assert_eq!(names.len(), 12);
+    for store in &["MemStore", "VecStore"] {
+        let prefix = format!("suite_module::kv::{}::", store);
+        assert_eq!(
+            names
+                .iter()
+                .filter(|name| name.starts_with(&prefix))
+                .count(),
+            6
+        );
+    }
+}
+
+#[test]
+fn suite_module_other_items() {
+    let store: MemStore = kv::new_store();
+    assert_eq!(store.get(0), None);
+}
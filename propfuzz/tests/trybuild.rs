@@ -7,4 +7,7 @@
 fn trybuild_tests() {
     let t = trybuild::TestCases::new();
     t.compile_fail("tests/compile-fail/*.rs");
+    // These only fail to compile with the corresponding feature disabled.
+    #[cfg(not(feature = "tokio"))]
+    t.compile_fail("tests/compile-fail/no-tokio/*.rs");
 }
@@ -0,0 +1,84 @@
+// Copyright (c) The propfuzz Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Tests for unstructured targets, which take `&[u8]` or `&str` arguments.
+
+use propfuzz::{
+    prelude::*,
+    runtime::{execute_as_fuzz_target, replay},
+    traits::StructuredTarget,
+};
+use proptest::collection::vec;
+
+/// Parsing arbitrary bytes as UTF-8 doesn't panic.
+#[propfuzz]
+fn parse_utf8(data: &[u8]) {
+    let _ = std::str::from_utf8(data);
+}
+
+/// Strategies can be customized for unstructured arguments.
+#[propfuzz]
+fn short_bytes(#[propfuzz(strategy = "vec(any::<u8>(), 0..8)")] data: &[u8]) {
+    prop_assert!(data.len() < 8);
+}
+
+/// Round-tripping a string through bytes produces the same string.
+#[propfuzz]
+fn roundtrip_str(s: &str) {
+    let bytes = s.as_bytes().to_vec();
+    prop_assert_eq!(String::from_utf8(bytes).unwrap(), s);
+}
+
+/// This test fails for inputs starting with "bad".
+#[propfuzz]
+#[ignore]
+fn no_bad_bytes(data: &[u8]) {
+    prop_assert!(!data.starts_with(b"bad"));
+}
+
+/// This test panics for the string "boom".
+#[propfuzz]
+#[ignore]
+fn no_boom(s: &str) {
+    assert_ne!(s, "boom");
+}
+
+#[test]
+fn unstructured_values() {
+    assert!(__PROPFUZZ__parse_utf8.is_unstructured());
+    assert_eq!(
+        __PROPFUZZ__parse_utf8.value_from_bytes(&[1, 2, 3]),
+        Some(vec![1, 2, 3])
+    );
+    assert_eq!(
+        __PROPFUZZ__roundtrip_str.value_from_bytes(b"abc"),
+        Some("abc".to_string())
+    );
+    assert_eq!(__PROPFUZZ__roundtrip_str.value_from_bytes(&[0xff]), None);
+}
+
+#[test]
+fn unstructured_fuzz_target_passes() {
+    execute_as_fuzz_target(__PROPFUZZ__no_bad_bytes, b"good");
+    execute_as_fuzz_target(__PROPFUZZ__no_boom, b"boo");
+    // Invalid UTF-8 is skipped.
+    execute_as_fuzz_target(__PROPFUZZ__no_boom, &[0xff, 0xfe]);
+}
+
+#[test]
+#[should_panic(expected = "failing input:\ndata = [98, 97, 100, 33]")]
+fn unstructured_fuzz_target_fails() {
+    execute_as_fuzz_target(__PROPFUZZ__no_bad_bytes, b"bad!");
+}
+
+#[test]
+#[should_panic(expected = "failing input:\ns = \"boom\"")]
+fn unstructured_fuzz_target_panics() {
+    execute_as_fuzz_target(__PROPFUZZ__no_boom, b"boom");
+}
+
+#[test]
+#[should_panic(expected = "Test aborted: input is not valid for this target")]
+fn unstructured_replay_invalid() {
+    replay(__PROPFUZZ__no_boom, &[0xff, 0xfe]);
+}